@@ -6,105 +6,1001 @@ use quote::{
 };
 use syn::{
     self,
+    parse::{
+        Parse,
+        ParseStream,
+    },
     parse_macro_input,
+    parse_quote,
     Ident,
     DeriveInput,
     Attribute,
     punctuated::Punctuated,
     Field,
+    LitInt,
+    LitStr,
+    Token,
 };
 
-fn parse_title(attrs: &Vec<Attribute>) -> Result<String, &'static str> {
-    match attrs.iter().find_map(|a| a.parse_meta().ok()).and_then(|m| match m {
-        syn::Meta::List(m) if m.path.to_token_stream().to_string() == "title" => {
-            Some(m.nested)
-        },
-        _ => None,
-    }) {
-        Some(m) => {
-            if m.len() != 1 {
-                return Err("#[title()] needs exactly one literal string argument");
+/// Collects errors so that all the problems in an item are reported at once,
+/// rather than just the first.
+#[derive(Default)]
+struct Errors(Option<syn::Error>);
+
+impl Errors {
+    fn push(&mut self, e: syn::Error) {
+        match &mut self.0 {
+            Some(errors) => errors.combine(e),
+            None => self.0 = Some(e),
+        }
+    }
+
+    /// Records the error if there is one, returning a placeholder value so that
+    /// generation can continue and find more errors.
+    fn check<T: Default>(&mut self, r: syn::Result<T>) -> T {
+        match r {
+            Ok(v) => return v,
+            Err(e) => {
+                self.push(e);
+                return T::default();
+            },
+        }
+    }
+
+    fn finish(self, out: TokenStream) -> syn::Result<TokenStream> {
+        match self.0 {
+            Some(e) => return Err(e),
+            None => return Ok(out),
+        }
+    }
+}
+
+/// The closest of `options` to `key` if there's one that's plausibly a typo.
+fn suggest(key: &str, options: &[&'static str]) -> Option<&'static str> {
+    /// Edit distance, counting swapped adjacent characters as one edit.
+    fn distance(a: &str, b: &str) -> usize {
+        let a = a.chars().collect::<Vec<_>>();
+        let b = b.chars().collect::<Vec<_>>();
+        let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+        for (i, row) in d.iter_mut().enumerate() {
+            row[0] = i;
+        }
+        for (j, cell) in d[0].iter_mut().enumerate() {
+            *cell = j;
+        }
+        for i in 1 ..= a.len() {
+            for j in 1 ..= b.len() {
+                let cost = if a[i - 1] == b[j - 1] {
+                    0
+                } else {
+                    1
+                };
+                d[i][j] = (d[i - 1][j] + 1).min(d[i][j - 1] + 1).min(d[i - 1][j - 1] + cost);
+                if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                    d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+                }
             }
-            match m.first().unwrap() {
-                syn::NestedMeta::Lit(l) => {
-                    match l {
-                        syn::Lit::Str(l) => {
-                            return Ok(
-                                litrs::StringLit::parse(l.to_token_stream().to_string()).unwrap().value().to_string(),
-                            );
-                        },
-                        _ => return Err("#[title()] needs exactly one literal string argument"),
+        }
+        return d[a.len()][b.len()];
+    }
+
+    return options
+        .iter()
+        .map(|o| (distance(key, o), *o))
+        .filter(|(d, o)| *d <= (o.len() / 3).max(1))
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, o)| o);
+}
+
+/// The `#[title("...")]` text. `ident` is the field or variant, where the error is
+/// reported if the title is missing. `fallback` is used if there's no `#[title]`
+/// (the serde rename).
+fn parse_title(attrs: &Vec<Attribute>, ident: &Ident, fallback: Option<&String>) -> syn::Result<String> {
+    let mut titles = attrs.iter().filter(|a| a.path.is_ident("title"));
+    let Some(a) = titles.next() else {
+        if let Some(fallback) = fallback {
+            return Ok(fallback.clone());
+        }
+        return Err(syn::Error::new(ident.span(), format!("Missing #[title(\"Field name\")] on {}", ident)));
+    };
+    if let Some(extra) = titles.next() {
+        return Err(syn::Error::new_spanned(extra, "Only one #[title()] is allowed"));
+    }
+    match a.parse_args::<LitStr>() {
+        Ok(l) => return Ok(l.value()),
+        Err(_) => return Err(
+            syn::Error::new_spanned(a, "#[title()] needs exactly one literal string argument"),
+        ),
+    }
+}
+
+/// The doc comment text, used as the JSON Schema description.
+fn parse_doc(attrs: &Vec<Attribute>) -> Option<String> {
+    let mut lines = vec![];
+    for m in attrs.iter().filter_map(|a| a.parse_meta().ok()) {
+        match m {
+            syn::Meta::NameValue(n) if n.path.to_token_stream().to_string() == "doc" => {
+                if let syn::Lit::Str(l) = n.lit {
+                    lines.push(l.value().trim().to_string());
+                }
+            },
+            _ => { },
+        }
+    }
+    let doc = lines.join("\n").trim().to_string();
+    if doc.is_empty() {
+        return None;
+    }
+    return Some(doc);
+}
+
+/// Adds the `description` to a schema value if there's a doc comment.
+fn build_describe(schema: TokenStream, attrs: &Vec<Attribute>) -> TokenStream {
+    let Some(doc) = parse_doc(attrs) else {
+        return schema;
+    };
+    return quote!{
+        {
+            let mut schema = #schema;
+            if let Some(o) = schema.as_object_mut() {
+                o.insert("description".to_string(), rooting_forms:: serde_json:: Value:: from(#doc));
+            }
+            schema
+        }
+    };
+}
+
+/// Builds an object schema expression for the fields of a struct or variant.
+fn build_fields_schema(title: TokenStream, fields: &[ParsedField]) -> TokenStream {
+    let mut properties = vec![];
+    for f in fields {
+        let f_name = &f.title;
+        let f_form = field_form(f);
+        let f_key = &f.key;
+        if f.serde_flatten {
+            let required = if f.default.is_some() {
+                quote!()
+            } else {
+                quote!{
+                    if let Some(r) = schema.get("required").and_then(| r | r.as_array()) {
+                        required.extend(r.iter().cloned());
+                    }
+                }
+            };
+            properties.push(quote!{
+                {
+                    let schema = #f_form:: schema(#f_name);
+                    if let Some(p) = schema.get("properties").and_then(| p | p.as_object()) {
+                        properties.extend(p.clone());
+                    }
+                    #required
+                }
+            });
+            continue;
+        }
+        let f_schema = build_describe(quote!(#f_form:: schema(#f_name)), &f.field.attrs);
+        let required = if f.default.is_some() {
+            quote!()
+        } else {
+            quote!{
+                if !#f_form:: OPTIONAL {
+                    required.push(rooting_forms:: serde_json:: Value:: from(#f_key));
+                }
+            }
+        };
+        properties.push(quote!{
+            properties.insert(#f_key.to_string(), #f_schema);
+            #required
+        });
+    }
+    return quote!{
+        {
+            let mut properties = rooting_forms::serde_json::Map::new();
+            let mut required: Vec<rooting_forms::serde_json::Value> = vec![];
+            #(#properties) * 
+            //. .
+            rooting_forms:: serde_json:: json!({
+                "type": "object",
+                "title": #title,
+                "properties": properties,
+                "required": required,
+                "additionalProperties": false
+            })
+        }
+    };
+}
+
+/// Pushes the label for a field onto `elements`. Flattened fields with no title
+/// have no label.
+fn build_label(title: &str) -> TokenStream {
+    if title.is_empty() {
+        return quote!();
+    }
+    return quote!{
+        elements.push(rooting_forms:: dom:: el("span").classes(&[rooting_forms::CSS_CLASS_LABEL]).text(#title));
+    };
+}
+
+/// Builds an expression for the display elements of the fields of a struct or
+/// variant. `access` produces a reference to a field's value.
+fn build_fields_display(fields: &[ParsedField], access: &dyn Fn(&Ident) -> TokenStream) -> TokenStream {
+    let mut fields_display = vec![];
+    for f in fields {
+        let f_name = &f.title;
+        let f_form = field_form(f);
+        let f_value = access(f.ident);
+        let label = build_label(f_name);
+        let inline = quote!{
+            #label 
+            //. .
+            elements.extend(subelements);
+        };
+        let mut f_display;
+        if f.attrs.flatten {
+            f_display = quote!{
+                let subelements = #f_form:: display(#f_name, #f_value);
+                #inline
+            };
+        } else {
+            f_display = quote!{
+                let subelements = #f_form:: display(#f_name, #f_value);
+                if #f_form:: FIELDSET {
+                    elements.push(
+                        rooting_forms:: dom:: el("fieldset")
+                            .classes(&[rooting_forms::CSS_CLASS_SUBFORM])
+                            .push(rooting_forms:: dom:: el("legend").text(#f_name))
+                            .extend(subelements)
+                    );
+                } else {
+                    #inline
+                }
+            };
+        }
+        if let Some((sibling, predicate)) = &f.attrs.visible_if {
+            let sibling_value = access(sibling);
+            let visible = match predicate {
+                Some(predicate) => quote!(#predicate(#sibling_value)),
+                None => quote!(*#sibling_value),
+            };
+            f_display = quote!{
+                if #visible {
+                    #f_display
+                }
+            };
+        }
+        fields_display.push(quote!{
+            {
+                #f_display
+            }
+        });
+    }
+    return quote!{
+        {
+            let mut elements: Vec<rooting_forms::dom::El> = vec![];
+            #(#fields_display) * 
+            //. .
+            elements
+        }
+    };
+}
+
+#[derive(Default)]
+struct FieldAttrs {
+    flatten: bool,
+    /// A `FormWith` widget to use instead of the field type's `Form` impl.
+    with: Option<syn::Path>,
+    /// The sibling field, and a predicate on its value. If no predicate is
+    /// specified, the sibling must be a `bool`.
+    visible_if: Option<(Ident, Option<syn::Path>)>,
+    /// The wizard step title, for `FormSteps`.
+    step: Option<LitStr>,
+}
+
+const FORM_ARGS: &[&'static str] = &["flatten", "with", "multi", "visible_if", "step"];
+
+/// A `#[form()]` argument.
+enum FormArg {
+    Flatten,
+    With(syn::Path),
+    /// A `CheckboxGroup`, with the optional min and max selected.
+    Multi(Option<LitInt>, Option<LitInt>),
+    VisibleIf(Ident, Option<syn::Path>),
+    Step(LitStr),
+}
+
+/// Parses `= value`, replacing any error with a description of the argument.
+fn parse_value<T: Parse>(input: ParseStream, message: &str) -> syn::Result<T> {
+    return input
+        .parse::<Token![=]>()
+        .and_then(|_| input.parse::<T>())
+        .map_err(|e| syn::Error::new(e.span(), message));
+}
+
+impl Parse for FormArg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key = input.parse::<Ident>()?;
+        match key.to_string().as_str() {
+            "flatten" => return Ok(FormArg::Flatten),
+            "with" => {
+                return Ok(FormArg::With(parse_value(input, "#[form(with = Widget)] needs a widget type")?));
+            },
+            "multi" => {
+                let mut min = None;
+                let mut max = None;
+                if input.peek(syn::token::Paren) {
+                    let args;
+                    syn::parenthesized!(args in input);
+                    while !args.is_empty() {
+                        let bound = args.parse::<Ident>()?;
+                        let value = parse_value::<LitInt>(&args, "#[form(multi())] bounds must be integers")?;
+                        match bound.to_string().as_str() {
+                            "min" => min = Some(value),
+                            "max" => max = Some(value),
+                            _ => {
+                                return Err(
+                                    syn::Error::new(
+                                        bound.span(),
+                                        "Unknown #[form(multi())] argument, expected `min` or `max`",
+                                    ),
+                                );
+                            },
+                        }
+                        if args.is_empty() {
+                            break;
+                        }
+                        args.parse::<Token![,]>()?;
+                    }
+                }
+                return Ok(FormArg::Multi(min, max));
+            },
+            "step" => {
+                return Ok(FormArg::Step(parse_value(input, "#[form(step = \"Step title\")] needs a string argument")?));
+            },
+            "visible_if" => {
+                if input.peek(syn::token::Paren) {
+                    let args;
+                    syn::parenthesized!(args in input);
+                    let sibling =
+                        args
+                            .parse::<Ident>()
+                            .map_err(|e| syn::Error::new(e.span(), "#[form(visible_if())] sibling must be a field name"))?;
+                    let predicate =
+                        parse_value::<LitStr>(&args, "#[form(visible_if())] predicate must be a string")?;
+                    if !args.is_empty() {
+                        return Err(
+                            args.error("#[form(visible_if(sibling = \"predicate\"))] needs exactly one sibling and predicate"),
+                        );
+                    }
+                    let predicate =
+                        predicate
+                            .parse::<syn::Path>()
+                            .map_err(
+                                |_| syn::Error::new(predicate.span(), "#[form(visible_if)] predicate must be a function path"),
+                            )?;
+                    return Ok(FormArg::VisibleIf(sibling, Some(predicate)));
+                }
+                let sibling =
+                    parse_value::<LitStr>(input, "#[form(visible_if = \"sibling\")] needs a string argument")?;
+                let sibling =
+                    sibling
+                        .parse::<Ident>()
+                        .map_err(|_| syn::Error::new(sibling.span(), "#[form(visible_if)] sibling must be a field name"))?;
+                return Ok(FormArg::VisibleIf(sibling, None));
+            },
+            other => {
+                let message = match suggest(other, FORM_ARGS) {
+                    Some(s) => format!("Unknown #[form()] argument `{}`, did you mean `{}`?", other, s),
+                    None => format!("Unknown #[form()] argument `{}`, expected one of: {}", other, FORM_ARGS.join(", ")),
+                };
+                return Err(syn::Error::new(key.span(), message));
+            },
+        }
+    }
+}
+
+/// A `FormArg` with its key, for reporting arguments that aren't supported where
+/// they're used.
+struct KeyedFormArg {
+    key: Ident,
+    arg: FormArg,
+}
+
+impl Parse for KeyedFormArg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        return Ok(KeyedFormArg {
+            key: input.fork().parse::<Ident>()?,
+            arg: input.parse()?,
+        });
+    }
+}
+
+/// Parses `#[form()]` on a field (or variant). `allowed` are the arguments supported
+/// on `place`, ex: `enum variants`; others are errors.
+fn parse_field_attrs(attrs: &Vec<Attribute>, allowed: &[&str], place: &str, errors: &mut Errors) -> FieldAttrs {
+    let mut out = FieldAttrs::default();
+    for a in attrs {
+        if !a.path.is_ident("form") {
+            continue;
+        }
+        let args = match a.parse_args_with(Punctuated::<KeyedFormArg, Token![,]>::parse_terminated) {
+            Ok(args) => args,
+            Err(e) => {
+                errors.push(e);
+                continue;
+            },
+        };
+        for KeyedFormArg { key, arg } in args {
+            if !allowed.iter().any(|k| key == k) {
+                errors.push(syn::Error::new(key.span(), format!("#[form({})] isn't supported on {}", key, place)));
+                continue;
+            }
+            match arg {
+                FormArg::Flatten => out.flatten = true,
+                FormArg::With(p) => {
+                    if out.with.is_some() {
+                        errors.push(syn::Error::new_spanned(a, "Only one of #[form(with)] and #[form(multi)] can be used"));
                     }
+                    out.with = Some(p);
                 },
-                _ => {
-                    return Err("#[title()] argument must be a string literal");
+                FormArg::Multi(min, max) => {
+                    if out.with.is_some() {
+                        errors.push(syn::Error::new_spanned(a, "Only one of #[form(with)] and #[form(multi)] can be used"));
+                    }
+                    let min = min.map(|m| m.to_token_stream()).unwrap_or(quote!(0));
+                    let max = max.map(|m| m.to_token_stream()).unwrap_or(quote!({
+                        usize::MAX
+                    }));
+                    out.with = Some(parse_quote!(rooting_forms:: CheckboxGroup < #min, #max >));
                 },
+                FormArg::VisibleIf(sibling, predicate) => out.visible_if = Some((sibling, predicate)),
+                FormArg::Step(step) => out.step = Some(step),
             }
-        },
-        None => return Err("Missing #[title(\"Field name\")]"),
+        }
+    }
+    return out;
+}
+
+/// Options from `#[form()]` on the struct or enum itself.
+#[derive(Default)]
+struct TypeAttrs {
+    /// Read `#[serde()]` attributes too.
+    serde: bool,
+    /// From `#[serde(default)]` on the type, fields default to the corresponding
+    /// field of this value.
+    serde_default: Option<TokenStream>,
+}
+
+const TYPE_FORM_ARGS: &[&'static str] = &["serde"];
+
+fn parse_type_attrs(attrs: &Vec<Attribute>, t_ident: &Ident, errors: &mut Errors) -> TypeAttrs {
+    let mut out = TypeAttrs::default();
+    for a in attrs {
+        if !a.path.is_ident("form") {
+            continue;
+        }
+        let args = match a.parse_args_with(Punctuated::<Ident, Token![,]>::parse_terminated) {
+            Ok(args) => args,
+            Err(e) => {
+                errors.push(e);
+                continue;
+            },
+        };
+        for arg in args {
+            if arg == "serde" {
+                out.serde = true;
+                continue;
+            }
+            let message = match suggest(&arg.to_string(), TYPE_FORM_ARGS) {
+                Some(s) => format!("Unknown #[form()] argument `{}` on a type, did you mean `{}`?", arg, s),
+                None => format!("Unknown #[form()] argument on a type, expected: {}", TYPE_FORM_ARGS.join(", ")),
+            };
+            errors.push(syn::Error::new(arg.span(), message));
+        }
+    }
+    if out.serde {
+        let serde = parse_serde_attrs(attrs, errors);
+        out.serde_default = serde.default.map(|default| match default {
+            Some(path) => quote!(#path()),
+            None => quote!(< #t_ident as Default >:: default()),
+        });
+    }
+    return out;
+}
+
+/// The `#[serde()]` attributes that affect forms, with `#[form(serde)]`. Others
+/// (and malformed attributes) are ignored, since serde reports those, except for
+/// `default` paths which are reported since the form would be missing the default.
+#[derive(Default)]
+struct SerdeAttrs {
+    rename: Option<String>,
+    skip: bool,
+    /// `default`, or `default = "path"`.
+    default: Option<Option<syn::Path>>,
+    flatten: bool,
+}
+
+fn parse_serde_attrs(attrs: &Vec<Attribute>, errors: &mut Errors) -> SerdeAttrs {
+    let mut out = SerdeAttrs::default();
+    for a in attrs {
+        if !a.path.is_ident("serde") {
+            continue;
+        }
+        let Ok(syn::Meta::List(list)) = a.parse_meta() else {
+            continue;
+        };
+        for m in list.nested {
+            let syn::NestedMeta::Meta(m) = m else {
+                continue;
+            };
+            match m {
+                syn::Meta::Path(p) => {
+                    if p.is_ident("skip") || p.is_ident("skip_deserializing") {
+                        out.skip = true;
+                    } else if p.is_ident("default") {
+                        out.default = Some(None);
+                    } else if p.is_ident("flatten") {
+                        out.flatten = true;
+                    }
+                },
+                syn::Meta::NameValue(n) => {
+                    let syn::Lit::Str(l) = n.lit else {
+                        continue;
+                    };
+                    if n.path.is_ident("rename") {
+                        out.rename = Some(l.value());
+                    } else if n.path.is_ident("default") {
+                        match l.parse::<syn::Path>() {
+                            Ok(path) => out.default = Some(Some(path)),
+                            Err(_) => errors.push(
+                                syn::Error::new(l.span(), "#[serde(default = \"...\")] needs a function path"),
+                            ),
+                        }
+                    }
+                },
+                syn::Meta::List(l) => {
+                    // `rename(serialize = "a", deserialize = "b")`, the deserialized name
+                    // matches what's entered
+                    if !l.path.is_ident("rename") {
+                        continue;
+                    }
+                    for n in l.nested {
+                        let syn::NestedMeta::Meta(syn::Meta::NameValue(n)) = n else {
+                            continue;
+                        };
+                        let syn::Lit::Str(v) = n.lit else {
+                            continue;
+                        };
+                        if n.path.is_ident("deserialize") || (n.path.is_ident("serialize") && out.rename.is_none()) {
+                            out.rename = Some(v.value());
+                        }
+                    }
+                },
+            }
+        }
+    }
+    return out;
+}
+
+/// A named field of a struct or variant, with its attributes parsed.
+struct ParsedField<'a> {
+    field: &'a Field,
+    ident: &'a Ident,
+    /// The schema property name, the field name or serde rename.
+    key: String,
+    /// Empty for flattened fields without `#[title]`.
+    title: String,
+    attrs: FieldAttrs,
+    /// `#[serde(flatten)]`, the field's properties are merged into the schema.
+    serde_flatten: bool,
+    /// The value used for new forms and left out of the schema `required`, from
+    /// `#[serde(default)]`.
+    default: Option<TokenStream>,
+    /// `default` is part of the type's `#[serde(default)]`.
+    type_default: bool,
+}
+
+/// A field left out of the form by `#[serde(skip)]`, and the value it gets.
+struct SkippedField<'a> {
+    ident: &'a Ident,
+    value: TokenStream,
+    /// `value` is part of the type's `#[serde(default)]`.
+    type_default: bool,
+}
+
+/// The named fields of a struct or variant.
+struct ParsedFields<'a> {
+    fields: Vec<ParsedField<'a>>,
+    skipped: Vec<SkippedField<'a>>,
+    /// The type's `#[serde(default)]` value if fields use it, bound once as
+    /// `__type_default` before the fields' default values are used.
+    type_default: Option<TokenStream>,
+}
+
+fn parse_fields<'a, X>(fields: &'a Punctuated<Field, X>, type_attrs: &TypeAttrs, errors: &mut Errors) -> ParsedFields<'a> {
+    let mut out = vec![];
+    let mut skipped = vec![];
+    for f in fields {
+        let Some(ident) = f.ident.as_ref() else {
+            continue;
+        };
+        let serde = if type_attrs.serde {
+            parse_serde_attrs(&f.attrs, errors)
+        } else {
+            SerdeAttrs::default()
+        };
+        let (default, type_default) = match (serde.default, &type_attrs.serde_default) {
+            (Some(Some(path)), _) => (Some(quote!(#path())), false),
+            (Some(None), _) => (Some(quote!(Default:: default())), false),
+            (None, Some(_)) => (Some(quote!(__type_default.#ident)), true),
+            (None, None) => (None, false),
+        };
+        if serde.skip {
+            skipped.push(SkippedField {
+                ident: ident,
+                value: default.unwrap_or_else(|| quote!(Default:: default())),
+                type_default: type_default,
+            });
+            continue;
+        }
+        let mut attrs = parse_field_attrs(&f.attrs, FORM_ARGS, "fields", errors);
+        attrs.flatten |= serde.flatten;
+        // Flattened fields don't get a heading, so the title is optional
+        let title_fallback = serde.rename.clone().or_else(|| attrs.flatten.then(String::new));
+        out.push(ParsedField {
+            field: f,
+            ident: ident,
+            key: serde.rename.unwrap_or_else(|| ident.to_string()),
+            title: errors.check(parse_title(&f.attrs, ident, title_fallback.as_ref())),
+            attrs: attrs,
+            serde_flatten: serde.flatten,
+            default: default,
+            type_default: type_default,
+        });
+    }
+    let uses_type_default = out.iter().any(|f| f.type_default) || skipped.iter().any(|f| f.type_default);
+    return ParsedFields {
+        fields: out,
+        skipped: skipped,
+        type_default: type_attrs.serde_default.clone().filter(|_| uses_type_default),
     };
 }
 
-fn build_fields_form<
-    X,
->(
+/// The trait impl used for a field's form, `display` and `schema`: `Form` for
+/// the field type, or the `FormWith` widget.
+fn field_form(f: &ParsedField) -> TokenStream {
+    return type_form(&f.field.ty, &f.attrs);
+}
+
+fn type_form(ty: &syn::Type, attrs: &FieldAttrs) -> TokenStream {
+    let f_type_ident = ty.to_token_stream();
+    match &attrs.with {
+        Some(with) => return quote!(< #with as rooting_forms:: FormWith < #f_type_ident >>),
+        None => return quote!(< #f_type_ident as rooting_forms:: Form >),
+    }
+}
+
+fn build_fields_form(
     form_ident: &Ident,
     value_type_ident: &Ident,
     value_construct_ident: &TokenStream,
-    fields: &Punctuated<Field, X>,
+    parsed: &ParsedFields,
+    field_from: &dyn Fn(&Ident) -> TokenStream,
+    steps: bool,
+    errors: &mut Errors,
 ) -> TokenStream {
+    let fields = &parsed.fields;
+    let bind_type_default = |used: bool| match (&parsed.type_default, used) {
+        (Some(type_default), true) => quote!(let __type_default = #type_default;),
+        _ => quote!(),
+    };
+    let new_bind_type_default = bind_type_default(fields.iter().any(|f| f.type_default));
+    let parse_bind_type_default =
+        bind_type_default(
+            fields.iter().any(|f| f.type_default && f.attrs.visible_if.is_some()) ||
+                parsed.skipped.iter().any(|f| f.type_default),
+        );
     let mut form_fields = vec![];
     let mut form_construct_fields = vec![];
     let mut form_elements = vec![];
     let mut form_parse = vec![];
+    let mut form_peek = vec![];
     let mut form_parse_assemble = vec![];
-    for f in fields {
-        let f_ident = f.ident.as_ref().unwrap();
-        let f_name = parse_title(&f.attrs).expect(&format!("Error with attributes on field {}", f_ident));
-        let f_type_ident = f.ty.to_token_stream();
+    let mut form_on_change = vec![];
+    let mut form_is_dirty = vec![];
+    let mut form_reset = vec![];
+    let mut form_get_raw = vec![];
+    let mut form_set_raw = vec![];
+    let mut form_field = vec![];
+    let mut form_set_disabled = vec![];
+    let mut form_visit_fields = vec![];
+    let mut form_visit_errors = vec![];
+    let mut step_titles = vec![];
+    let mut step_parse: Vec<Vec<TokenStream>> = vec![];
+    for (f_index, f) in fields.iter().enumerate() {
+        let f_ident = f.ident;
+        let f_name = &f.title;
+        let f_attrs = &f.attrs;
+        let f_type_ident = f.field.ty.to_token_stream();
+        let f_form = field_form(f);
+        match (&f_attrs.step, steps) {
+            (Some(step), false) => {
+                errors.push(syn::Error::new(step.span(), "#[form(step)] is only supported in structs"));
+            },
+            (Some(step), true) => {
+                step_titles.push(step.value());
+                step_parse.push(vec![]);
+            },
+            (None, true) if step_titles.is_empty() => {
+                errors.push(
+                    syn::Error::new(
+                        f_ident.span(),
+                        format!("The first field {} needs a #[form(step = \"Step title\")]", f_ident),
+                    ),
+                );
+                step_titles.push(String::new());
+                step_parse.push(vec![]);
+            },
+            (None, _) => { },
+        }
+        let step_push;
+        if steps {
+            let step_index = step_titles.len() - 1;
+            step_push = quote!(step_elements[#step_index].extend(elements.clone()););
+        } else {
+            step_push = quote!();
+        }
         form_fields.push(quote!{
-            #f_ident: Box < dyn rooting_forms:: FormState < #f_type_ident >>,
+            #f_ident: std:: rc:: Rc < dyn rooting_forms:: FormState < #f_type_ident >>,
         });
+        let f_from = field_from(f_ident);
+        let f_new_form = match &f.default {
+            Some(default) => quote!(match #f_from {
+                Some(v) => #f_form:: new_form_with(#f_name, Some(v)),
+                None => #f_form:: new_form_with(#f_name, Some(&#default)),
+            }),
+            None => quote!(#f_form:: new_form_with(#f_name, #f_from)),
+        };
         form_construct_fields.push(quote!{
-            #f_ident: #f_type_ident:: new_form(#f_name),
+            #f_ident: std:: rc:: Rc:: from(#f_new_form),
         });
-        form_elements.push(quote!{
-            {
-                let subelements = self.#f_ident.elements();
-                elements.extend(subelements.error.into_iter());
-                elements.push(rooting:: el("span").classes(&[rooting_forms::CSS_CLASS_LABEL]).text(#f_name));
-                elements.extend(subelements.elements);
-            }
-        });
-        form_parse.push(quote!{
-            let #f_ident = match self.#f_ident.parse() {
-                Ok(v) => Some(v),
-                Err(e) => {
-                    errored = true;
-                    None
+        let label = build_label(f_name);
+        let inline = quote!{
+            elements.extend(subelements.error.into_iter());
+            #label 
+            //. .
+            elements.extend(subelements.elements);
+        };
+        let build_elements;
+        if f_attrs.flatten {
+            build_elements = quote!{
+                let subelements = state.#f_ident.elements();
+                #inline
+            };
+        } else {
+            build_elements = quote!{
+                let subelements = state.#f_ident.elements();
+                if #f_form:: FIELDSET {
+                    elements.push(
+                        rooting_forms:: dom:: el("fieldset")
+                            .classes(&[rooting_forms::CSS_CLASS_SUBFORM])
+                            .push(rooting_forms:: dom:: el("legend").text(#f_name))
+                            .extend(subelements.error.into_iter().collect())
+                            .extend(subelements.elements)
+                    );
+                } else {
+                    #inline
                 }
             };
-        });
+        }
+        if let Some((sibling, predicate)) = &f_attrs.visible_if {
+            if !fields.iter().any(|f| f.ident == sibling) {
+                errors.push(
+                    syn::Error::new(
+                        sibling.span(),
+                        format!("#[form(visible_if)] on field {} refers to missing sibling {}", f_ident, sibling),
+                    ),
+                );
+            }
+            // `peek` so that checking visibility as the sibling is edited doesn't show
+            // its validation errors
+            let visible = |sibling_state: TokenStream| match predicate {
+                Some(predicate) => quote!(#sibling_state.peek().map(| v | #predicate(&v)).unwrap_or(false)),
+                None => quote!(#sibling_state.peek().unwrap_or(false)),
+            };
+            let update_visible = visible(quote!(sibling));
+            form_elements.push(quote!{
+                {
+                    let mut elements = vec![];
+                    #build_elements 
+                    //. .
+                    let update: std:: rc:: Rc < dyn Fn() >= std:: rc:: Rc:: new({
+                        let sibling = std:: rc:: Rc:: downgrade(&state.#sibling);
+                        let elements = elements.clone();
+                        move || {
+                            let Some(sibling) = sibling.upgrade() else {
+                                return;
+                            };
+                            let visible = #update_visible;
+                            for e in &elements {
+                                e.ref_modify_classes(&[(rooting_forms::CSS_CLASS_HIDDEN, !visible)]);
+                            }
+                        }
+                    });
+                    update();
+                    state.#sibling.on_change(update.clone());
+                    state.__visibility.push(update);
+                    #step_push 
+                    //. .
+                    state.__elements.extend(elements);
+                }
+            });
+            let parse_visible = visible(quote!(self.#sibling));
+            let f_ident_str = f_ident.to_string();
+            form_visit_errors.push(quote!{
+                if #parse_visible {
+                    path.push(#f_ident_str);
+                    self.#f_ident.visit_errors(path, f);
+                    path.pop();
+                }
+            });
+            let hidden_value = f.default.clone().unwrap_or_else(|| quote!(Default:: default()));
+            for (out, method) in [(&mut form_parse, quote!(parse)), (&mut form_peek, quote!(peek))] {
+                out.push(quote!{
+                    let #f_ident = if #parse_visible {
+                        match self.#f_ident.#method() {
+                            Ok(v) => Some(v),
+                            Err(e) => {
+                                errored = true;
+                                None
+                            }
+                        }
+                    } else {
+                        Some(#hidden_value)
+                    };
+                });
+            }
+        } else {
+            let f_ident_str = f_ident.to_string();
+            form_visit_errors.push(quote!{
+                path.push(#f_ident_str);
+                self.#f_ident.visit_errors(path, f);
+                path.pop();
+            });
+            form_elements.push(quote!{
+                {
+                    let mut elements = vec![];
+                    #build_elements 
+                    //. .
+                    #step_push 
+                    //. .
+                    state.__elements.extend(elements);
+                }
+            });
+            for (out, method) in [(&mut form_parse, quote!(parse)), (&mut form_peek, quote!(peek))] {
+                out.push(quote!{
+                    let #f_ident = match self.#f_ident.#method() {
+                        Ok(v) => Some(v),
+                        Err(e) => {
+                            errored = true;
+                            None
+                        }
+                    };
+                });
+            }
+        }
+        if steps {
+            step_parse.last_mut().unwrap().push(form_parse.last().unwrap().clone());
+        }
         form_parse_assemble.push(quote!{
             #f_ident: #f_ident.unwrap(),
         });
+        form_on_change.push(quote!{
+            self.#f_ident.on_change(cb.clone());
+        });
+        form_is_dirty.push(quote!{
+            if self.#f_ident.is_dirty() {
+                return true;
+            }
+        });
+        form_reset.push(quote!{
+            self.#f_ident.reset();
+        });
+        form_get_raw.push(quote!{
+            self.#f_ident.get_raw(),
+        });
+        form_set_raw.push(quote!{
+            self.#f_ident.set_raw(&raw[#f_index]);
+        });
+        let f_ident_str = f_ident.to_string();
+        form_field.push(quote!{
+            #f_ident_str => return self.#f_ident.field(path),
+        });
+        form_set_disabled.push(quote!{
+            self.#f_ident.set_disabled(disabled);
+        });
+        form_visit_fields.push(quote!{
+            path.push(#f_ident_str);
+            self.#f_ident.visit_fields(path, f);
+            path.pop();
+        });
+    }
+    let steps_impl;
+    let build_result;
+    if steps {
+        let step_count = step_titles.len();
+        let step_indices = 0 .. step_count;
+        let step_indices2 = 0 .. step_count;
+        let step_parse = step_parse.iter().map(|p| quote!(#(#p) *));
+        steps_impl = quote!{
+            impl #form_ident {
+                #[allow(unused_variables)] fn validate_step(&self, step: usize) -> bool {
+                    #parse_bind_type_default 
+                    //. .
+                    let mut errored = false;
+                    match step {
+                        #(#step_indices => {
+                            #step_parse
+                        }) * 
+                        //. .
+                        _ => unreachable!(),
+                    }
+                    return !errored;
+                }
+            }
+        };
+        build_result = quote!{
+            let state = std:: rc:: Rc:: new(state);
+            let mut steps = vec![];
+            #(
+                steps.push(rooting_forms::FormStep {
+                    title: #step_titles,
+                    elements: step_elements[#step_indices2].clone(),
+                    validate: Box:: new({
+                        let state = state.clone();
+                        move || state.validate_step(#step_indices2)
+                    }),
+                });
+            ) * 
+            //. .
+            (Box:: new(state) as Box < dyn rooting_forms:: FormState < #value_type_ident >>, steps)
+        };
+    } else {
+        steps_impl = quote!();
+        build_result = quote!(Box:: new(state));
+    }
+    let step_elements;
+    if steps {
+        let step_count = step_titles.len();
+        step_elements = quote!(let mut step_elements: Vec < Vec < rooting_forms:: dom:: El >>= vec![vec![]; #step_count];);
+    } else {
+        step_elements = quote!();
+    }
+    for f in &parsed.skipped {
+        let f_ident = f.ident;
+        let value = &f.value;
+        form_parse_assemble.push(quote!{
+            #f_ident: #value,
+        });
     }
+    let field_count = fields.len();
     return quote!{
         #[allow(non_camel_case_types)] struct #form_ident {
-            #(#form_fields) *
+            #(#form_fields) * 
+            //. .
+            __elements: Vec<rooting_forms::dom::El>,
+            __visibility: Vec<std::rc::Rc<dyn Fn()>>,
         }
         impl rooting_forms:: FormState < #value_type_ident > for #form_ident {
             fn elements(&self) -> rooting_forms:: FormElements {
-                let mut elements = Vec::new();
-                #(#form_elements) * 
-                //. .
                 return rooting_forms:: FormElements {
                     error: None,
-                    elements: elements
+                    elements: self.__elements.clone(),
                 };
             }
             fn parse(&self) -> Result < #value_type_ident,
             () > {
+                #parse_bind_type_default 
+                //. .
                 let mut errored = false;
                 #(#form_parse) * 
                 //. .
@@ -115,210 +1011,400 @@ fn build_fields_form<
                     #(#form_parse_assemble) *
                 });
             }
+            fn peek(&self) -> Result < #value_type_ident,
+            () > {
+                #parse_bind_type_default 
+                //. .
+                let mut errored = false;
+                #(#form_peek) * 
+                //. .
+                if errored {
+                    return Err(());
+                }
+                return Ok(#value_construct_ident {
+                    #(#form_parse_assemble) *
+                });
+            }
+            fn on_change(&self, cb: std::rc::Rc<dyn Fn()>) {
+                #(#form_on_change) *
+            }
+            fn is_dirty(&self) -> bool {
+                #(#form_is_dirty) * 
+                //. .
+                return false;
+            }
+            fn reset(&self) {
+                #(#form_reset) * 
+                //. .
+                for update in &self.__visibility {
+                    update();
+                }
+            }
+            fn set_disabled(&self, disabled: bool) {
+                #(#form_set_disabled) *
+            }
+            fn get_raw(&self) -> rooting_forms:: RawState {
+                return rooting_forms:: RawState:: List(vec![#(#form_get_raw) *]);
+            }
+            fn set_raw(&self, raw: &rooting_forms::RawState) {
+                let rooting_forms:: RawState:: List(raw) = raw else {
+                    return;
+                };
+                if raw.len() != #field_count {
+                    return;
+                }
+                #(#form_set_raw) * 
+                //. .
+                for update in &self.__visibility {
+                    update();
+                }
+            }
+            fn field(&self, path: &[&str]) -> Option<rooting_forms::FieldHandle> {
+                let Some((first, path)) = path.split_first() else {
+                    return None;
+                };
+                match *first {
+                    #(#form_field) * 
+                    //. .
+                    _ => return None,
+                }
+            }
+            fn visit_fields<'a>(
+                &'a self,
+                path: &mut Vec<&'a str>,
+                f: &mut dyn FnMut(&[&'a str], rooting_forms::FieldHandle),
+            ) {
+                #(#form_visit_fields) * 
+            }
+            fn visit_errors<'a>(
+                &'a self,
+                path: &mut Vec<&'a str>,
+                f: &mut dyn FnMut(&[&'a str], rooting_forms::FieldHandle),
+            ) {
+                #(#form_visit_errors) * 
+            }
         }
-        Box:: new(#form_ident {
-            #(#form_construct_fields) *
-        })
+        #steps_impl 
+        //. .
+        #new_bind_type_default 
+        //. .
+        let mut state = #form_ident {
+            #(#form_construct_fields) * 
+            //. .
+            __elements: vec![],
+            __visibility: vec![],
+        };
+        #step_elements
+        #(#form_elements) * 
+        //. .
+        #build_result
     };
 }
 
-fn derive1(body: DeriveInput) -> TokenStream {
+fn derive1(body: DeriveInput) -> syn::Result<TokenStream> {
     let t_ident = &body.ident;
+    let t_ident_str = t_ident.to_string();
+    let mut errors = Errors::default();
+    let type_attrs = parse_type_attrs(&body.attrs, t_ident, &mut errors);
     match body.data {
         syn::Data::Struct(s) => {
             match s.fields {
                 syn::Fields::Named(fields) => {
+                    let parsed = parse_fields(&fields.named, &type_attrs, &mut errors);
+                    let fields = &parsed.fields;
+                    let steps = fields.iter().any(|f| f.attrs.step.is_some());
                     let form_build =
                         build_fields_form(
                             &format_ident!("FormStateImpl"),
                             &t_ident,
                             &t_ident.to_token_stream(),
-                            &fields.named,
+                            &parsed,
+                            &|f_ident| quote!(from.map(| from | &from.#f_ident)),
+                            steps,
+                            &mut errors,
                         );
-                    return quote!{
+                    let schema = build_describe(build_fields_schema(quote!(title), fields), &body.attrs);
+                    let display = build_fields_display(fields, &|f_ident| quote!(&value.#f_ident));
+                    let form_schema = quote!{
+                        fn display(_field: &str, value: &Self) -> Vec<rooting_forms::dom::El> {
+                            return #display;
+                        }
+                        fn schema(title: &str) -> rooting_forms:: serde_json:: Value {
+                            return rooting_forms:: recursive_schema(#t_ident_str, title, || #schema);
+                        }
+                    };
+                    if steps {
+                        return errors.finish(quote!{
+                            impl rooting_forms:: Form for #t_ident {
+                                const FIELDSET: bool = true;
+                                fn new_form_with(
+                                    _field: &str,
+                                    from: Option<&Self>
+                                ) -> Box < dyn rooting_forms:: FormState < Self >> {
+                                    return < Self as rooting_forms:: FormSteps >:: new_steps_form(from).0;
+                                }
+                                #form_schema
+                            }
+                            impl rooting_forms:: FormSteps for #t_ident {
+                                fn new_steps_form(
+                                    from: Option<&Self>
+                                ) -> (Box < dyn rooting_forms:: FormState < Self >>, Vec < rooting_forms:: FormStep >) {
+                                    use rooting_forms::FormState;
+                                    use std::str::FromStr;
+                                    #form_build
+                                }
+                            }
+                        });
+                    }
+                    return errors.finish(quote!{
                         impl rooting_forms:: Form for #t_ident {
-                            fn new_form(field: &str) -> Box < dyn rooting_forms:: FormState < Self >> {
+                            const FIELDSET: bool = true;
+                            fn new_form_with(
+                                field: &str,
+                                from: Option<&Self>
+                            ) -> Box < dyn rooting_forms:: FormState < Self >> {
                                 use rooting_forms::FormState;
                                 use std::str::FromStr;
-                                use wasm_bindgen::JsCast;
                                 #form_build
                             }
+                            #form_schema
                         }
-                    };
+                    });
+                },
+                syn::Fields::Unnamed(fields) => {
+                    return Err(syn::Error::new_spanned(fields, "Tuple structs aren't supported"));
+                },
+                syn::Fields::Unit => {
+                    return Err(syn::Error::new(t_ident.span(), "Unit structs aren't supported"));
                 },
-                syn::Fields::Unnamed(_) => panic!("Tuple structs aren't supported"),
-                syn::Fields::Unit => panic!("Unit structs aren't supported"),
             }
         },
         syn::Data::Enum(e) => {
+            if e.variants.is_empty() {
+                return Err(syn::Error::new(t_ident.span(), "Empty enums aren't supported"));
+            }
             let mut build_variants = vec![];
+            let mut initial_variants = vec![];
+            let mut variant_schemas = vec![];
+            let mut variant_displays = vec![];
+            let mut choice_values = vec![];
+            let mut choice_indices = vec![];
             for (i, v) in e.variants.iter().enumerate() {
                 let v_ident = &v.ident;
-                let v_name =
-                    parse_title(&v.attrs).expect(&format!("Error with attributes on {}::{}", t_ident, v_ident));
-                let v_value = format!("{}", i);
-                let build_option;
-                {
-                    let selected;
-                    if i == 0 {
-                        selected = quote!(.attr("selected", "selected"));
-                    } else {
-                        selected = quote!();
-                    }
-                    build_option = quote!{
-                        select.ref_push(rooting:: el("option").text(#v_name).attr("value", #v_value) #selected);
-                    };
-                }
-                let container = quote!(rooting::el("div").classes(&[rooting_forms::CSS_CLASS_SUBFORM]));
+                let v_rename = if type_attrs.serde {
+                    parse_serde_attrs(&v.attrs, &mut errors).rename
+                } else {
+                    None
+                };
+                let v_name = errors.check(parse_title(&v.attrs, v_ident, v_rename.as_ref()));
+                parse_field_attrs(&v.attrs, &[], "enum variants", &mut errors);
+                let v_ident_str = v_ident.to_string();
+                // The serialized name, for the schema
+                let v_key = v_rename.unwrap_or_else(|| v_ident_str.clone());
+                initial_variants.push(quote!{
+                    Some(#t_ident:: #v_ident {
+                        ..
+                    }) => #i,
+                });
+                let build_subform;
+                let v_schema;
                 match &v.fields {
                     syn::Fields::Named(fields) => {
+                        let parsed = parse_fields(&fields.named, &type_attrs, &mut errors);
+                        let fields = &parsed.fields;
+                        let fields_schema = build_fields_schema(quote!(#v_name), fields);
+                        let binds = fields.iter().map(|f| {
+                            let f_ident = f.ident;
+                            let bind = format_ident!("v_{}", f_ident);
+                            return quote!(#f_ident: #bind,);
+                        }).collect::<Vec<_>>();
+                        let fields_display =
+                            build_fields_display(fields, &|f_ident| format_ident!("v_{}", f_ident).to_token_stream());
+                        variant_displays.push(quote!{
+                            #t_ident:: #v_ident {
+                                #(#binds) * ..
+                            } =>(#v_name, #fields_display),
+                        });
+                        v_schema = quote!{
+                            {
+                                let fields_schema = #fields_schema;
+                                rooting_forms:: serde_json:: json!({
+                                    "type": "object",
+                                    "title": #v_name,
+                                    "properties": {
+                                        #v_key: fields_schema
+                                    },
+                                    "required": [#v_key],
+                                    "additionalProperties": false
+                                })
+                            }
+                        };
                         let subform_build =
                             build_fields_form(
                                 &format_ident!("{}_{}_FormState", t_ident, v.ident),
                                 &t_ident,
                                 &quote!(#t_ident:: #v_ident),
-                                &fields.named,
+                                &parsed,
+                                &|f_ident| quote!(match from {
+                                    Some(#t_ident:: #v_ident {
+                                        #f_ident: v,
+                                        ..
+                                    }) => Some(v),
+                                    _ => None,
+                                }),
+                                false,
+                                &mut errors,
                             );
-                        build_variants.push(quote!{
+                        build_subform = quote!{
                             {
-                                #build_option 
-                                //. .
-                                let subform = {
-                                    #subform_build
-                                };
-                                let subform_elements = subform.elements();
-                                let container = #container;
-                                if let Some(error) = subform_elements.error {
-                                    container.ref_push(error);
-                                }
-                                container.ref_extend(subform_elements.elements);
-                                variant_elements.push(container);
-                                variant_parse.push(Box::new(move || subform.parse()));
+                                #subform_build
                             }
-                        });
+                        };
                     },
                     syn::Fields::Unnamed(fields) => {
-                        if fields.unnamed.len() != 1 {
-                            panic!(
-                                "Only single field tuple enum variants are supported currently ({}::{})",
-                                t_ident,
-                                v_ident
+                        let (Some(f), 1) = (fields.unnamed.first(), fields.unnamed.len()) else {
+                            errors.push(
+                                syn::Error::new_spanned(
+                                    fields,
+                                    "Only single field tuple enum variants are supported currently",
+                                ),
                             );
-                        }
-                        let f = fields.unnamed.first().unwrap();
-                        let f_type_ident = f.ty.to_token_stream();
-                        build_variants.push(quote!{
+                            continue;
+                        };
+                        let f_attrs = parse_field_attrs(&f.attrs, &["with", "multi"], "tuple variant fields", &mut errors);
+                        let f_form = type_form(&f.ty, &f_attrs);
+                        variant_displays.push(quote!{
+                            #t_ident:: #v_ident(v) =>(#v_name, #f_form:: display(#v_name, v)),
+                        });
+                        v_schema = quote!{
                             {
-                                #build_option 
-                                //. .
-                                let subform = #f_type_ident:: new_form(#v_name);
-                                let subform_elements = subform.elements();
-                                let container = #container;
-                                if let Some(error) = subform_elements.error {
-                                    container.ref_push(error);
-                                }
-                                container.ref_extend(subform_elements.elements);
-                                variant_elements.push(container);
-                                variant_parse.push(
-                                    Box:: new(move || subform.parse().map(| v | #t_ident:: #v_ident(v)))
-                                );
+                                let value_schema = #f_form:: schema(#v_name);
+                                rooting_forms:: serde_json:: json!({
+                                    "type": "object",
+                                    "title": #v_name,
+                                    "properties": {
+                                        #v_key: value_schema
+                                    },
+                                    "required": [#v_key],
+                                    "additionalProperties": false
+                                })
                             }
-                        });
+                        };
+                        build_subform = quote!{
+                            rooting_forms:: MapFormState:: new(
+                                #f_form:: new_form_with(#v_name, match from {
+                                    Some(#t_ident:: #v_ident(v)) => Some(v),
+                                    _ => None,
+                                }),
+                                | v | #t_ident:: #v_ident(v)
+                            )
+                        };
                     },
                     syn::Fields::Unit => {
-                        build_variants.push(quote!{
-                            {
-                                #build_option 
-                                //. .
-                                variant_parse.push(Box:: new(|| Ok(#t_ident:: #v_ident)));
-                                variant_elements.push(#container);
+                        variant_displays.push(quote!{
+                            #t_ident:: #v_ident =>(#v_name, vec![]),
+                        });
+                        v_schema = quote!{
+                            rooting_forms:: serde_json:: json!({
+                                "title": #v_name,
+                                "const": #v_key
+                            })
+                        };
+                        build_subform = quote!{
+                            rooting_forms:: UnitFormState:: new(|| #t_ident:: #v_ident)
+                        };
+                        choice_values.push(quote!{
+                            rooting_forms::Choice {
+                                name: #v_ident_str,
+                                key: #v_key,
+                                title: #v_name,
+                                value: #t_ident:: #v_ident,
                             }
                         });
+                        choice_indices.push(quote!(#t_ident:: #v_ident => #i,));
                     },
                 }
+                variant_schemas.push(build_describe(v_schema, &v.attrs));
+                build_variants.push(quote!{
+                    variants.push(
+                        rooting_forms:: EnumVariant:: new(
+                            #v_ident_str,
+                            #v_name,
+                            initial_variant == #i,
+                            from,
+                            | from | #build_subform
+                        )
+                    );
+                });
             }
-            return quote!{
-                impl rooting_forms:: Form for #t_ident {
-                    fn new_form(field: &str) -> Box < dyn rooting_forms:: FormState < Self >> {
-                        use rooting_forms::FormState;
-                        use std::str::FromStr;
-                        use wasm_bindgen::JsCast;
-                        struct FormStateImpl {
-                            select: rooting::El,
-                            variant_parse: Vec < Box < dyn Fn() -> Result < #t_ident,
-                            () >>>,
-                            variant_elements: Vec<rooting::El>,
-                            current_variant: std::rc::Rc<std::cell::Cell<usize>>,
+            // Unit-only enums can also be used in a `CheckboxGroup`
+            let mut choices = quote!();
+            if !e.variants.is_empty() && choice_values.len() == e.variants.len() {
+                choices = quote!{
+                    impl rooting_forms:: FormChoices for #t_ident {
+                        fn choices() -> Vec<rooting_forms::Choice<Self>> {
+                            return vec![#(#choice_values,) *];
                         }
-                        impl rooting_forms:: FormState < #t_ident > for FormStateImpl {
-                            fn elements(&self) -> rooting_forms::FormElements {
-                                let mut out = vec![];
-                                out.push(self.select.clone());
-                                out.extend(self.variant_elements.clone());
-                                return rooting_forms::FormElements {
-                                    error: None,
-                                    elements: out,
-                                };
-                            }
-                            fn parse(&self) -> Result < #t_ident,
-                            () > {
-                                return self.variant_parse[self.current_variant.get()]();
+                        fn choice_index(&self) -> usize {
+                            match self {
+                                #(#choice_indices) * 
                             }
                         }
-                        let variant = std::rc::Rc::new(std::cell::Cell::new(0));
-                        let mut elements = vec![];
-                        let select =
-                            rooting::el("select")
-                                .classes(&[rooting_forms::CSS_CLASS_SMALL_INPUT])
-                                .attr(rooting_forms::ATTR_LABEL, field);
-                        elements.push(select.clone());
-                        let mut variant_parse: Vec < Box < dyn Fn() -> Result < #t_ident,
-                        () >>>
-                        //. 
-                        = vec ![];
-                        let mut variant_elements = vec![];
+                    }
+                };
+            }
+            let enum_schema = build_describe(quote!{
+                {
+                    let variants = vec![#(#variant_schemas,) *];
+                    rooting_forms:: serde_json:: json!({
+                        "title": title,
+                        "oneOf": variants
+                    })
+                }
+            }, &body.attrs);
+            return errors.finish(quote!{
+                impl rooting_forms:: Form for #t_ident {
+                    fn new_form_with(field: &str, from: Option<&Self>) -> Box < dyn rooting_forms:: FormState < Self >> {
+                        use rooting_forms::FormState;
+                        let initial_variant = match from {
+                            #(#initial_variants) * 
+                            //. .
+                            None => 0,
+                        };
+                        let mut variants: Vec<rooting_forms::EnumVariant<#t_ident>> = vec![];
                         #(#build_variants) * 
                         //. .
-                        select.ref_on("change", {
-                            let variant_elements = variant_elements.clone();
-                            let variant = variant.clone();
-                            move |event| {
-                                let index =
-                                    usize::from_str(
-                                        &event
-                                            .target()
-                                            .unwrap()
-                                            .dyn_into::<rooting_forms::republish::HtmlSelectElement>()
-                                            .unwrap()
-                                            .value(),
-                                    ).unwrap();
-                                variant.set(index);
-                                for (e_index, v) in variant_elements.iter().enumerate() {
-                                    v.ref_modify_classes(&[(rooting_forms::CSS_CLASS_HIDDEN, e_index != index)]);
-                                }
-                            }
-                        });
-                        for v in &variant_elements[1..] {
-                            v.ref_classes(&[rooting_forms::CSS_CLASS_HIDDEN]);
-                        }
-                        return Box::new(FormStateImpl {
-                            select: select,
-                            variant_parse: variant_parse,
-                            variant_elements: variant_elements,
-                            current_variant: variant,
-                        });
+                        return rooting_forms::EnumFormState::new(field, initial_variant, variants).unwrap();
+                    }
+                    fn display(field: &str, value: &Self) -> Vec<rooting_forms::dom::El> {
+                        let (title, subelements) = match value {
+                            #(#variant_displays) * 
+                        };
+                        return vec![
+                            rooting_forms::display_text(field, title),
+                            rooting_forms::dom::el("div").classes(&[rooting_forms::CSS_CLASS_SUBFORM]).extend(subelements)
+                        ];
+                    }
+                    fn schema(title: &str) -> rooting_forms:: serde_json:: Value {
+                        return rooting_forms:: recursive_schema(#t_ident_str, title, || #enum_schema);
                     }
                 }
-            }
+                #choices
+            })
+        },
+        syn::Data::Union(u) => {
+            return Err(syn::Error::new(u.union_token.span, "Union types aren't supported"));
         },
-        syn::Data::Union(_) => panic!("Union types unsupported"),
     };
 }
 
-#[proc_macro_derive(Form, attributes(title))]
+#[proc_macro_derive(Form, attributes(title, form))]
 pub fn derive(body: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let ast = parse_macro_input!(body as syn::DeriveInput);
-    return derive1(ast).into();
+    match derive1(ast) {
+        Ok(out) => return out.into(),
+        Err(e) => return e.to_compile_error().into(),
+    }
 }
 
 #[cfg(test)]
@@ -336,7 +1422,7 @@ mod tests {
     };
 
     fn comp(got: &'static str, expected: TokenStream) {
-        let got = derive1(syn::parse2(TokenStream::from_str(got).unwrap()).unwrap());
+        let got = derive1(syn::parse2(TokenStream::from_str(got).unwrap()).unwrap()).unwrap();
         let cfg = FormatConfig::default();
         let mut s =
             [&got, &expected]
@@ -374,29 +1460,24 @@ struct Alpha {
 }
 "#, quote!(
             impl rooting_forms::Form for Alpha {
-                fn new_form(field: &str) -> Box<dyn rooting_forms::FormState<Self>> {
+                const FIELDSET: bool = true;
+
+                fn new_form_with(field: &str, from: Option<&Self>) -> Box<dyn rooting_forms::FormState<Self>> {
                     use rooting_forms::FormState;
                     use std::str::FromStr;
-                    use wasm_bindgen::JsCast;
 
+                    #[allow(non_camel_case_types)]
                     struct FormStateImpl {
-                        a: Box<dyn rooting_forms::FormState<i32>>,
+                        a: std::rc::Rc<dyn rooting_forms::FormState<i32>>,
+                        __elements: Vec<rooting_forms::dom::El>,
+                        __visibility: Vec<std::rc::Rc<dyn Fn()>>,
                     }
 
                     impl rooting_forms::FormState<Alpha> for FormStateImpl {
                         fn elements(&self) -> rooting_forms::FormElements {
-                            let mut elements = Vec::new();
-                            {
-                                let subelements = self.a.elements();
-                                elements.extend(subelements.error.into_iter());
-                                elements.push(
-                                    rooting::el("span").classes(&[rooting_forms::CSS_CLASS_LABEL]).text("A"),
-                                );
-                                elements.extend(subelements.elements);
-                            }
                             return rooting_forms::FormElements {
                                 error: None,
-                                elements: elements,
+                                elements: self.__elements.clone(),
                             };
                         }
 
@@ -412,11 +1493,157 @@ struct Alpha {
                             if errored {
                                 return Err(());
                             }
-                            return Alpha { a: a.unwrap() };
+                            return Ok(Alpha { a: a.unwrap() });
+                        }
+
+                        fn peek(&self) -> Result<Alpha, ()> {
+                            let mut errored = false;
+                            let a = match self.a.peek() {
+                                Ok(v) => Some(v),
+                                Err(e) => {
+                                    errored = true;
+                                    None
+                                },
+                            };
+                            if errored {
+                                return Err(());
+                            }
+                            return Ok(Alpha { a: a.unwrap() });
+                        }
+
+                        fn on_change(&self, cb: std::rc::Rc<dyn Fn()>) {
+                            self.a.on_change(cb.clone());
+                        }
+
+                        fn is_dirty(&self) -> bool {
+                            if self.a.is_dirty() {
+                                return true;
+                            }
+                            return false;
+                        }
+
+                        fn reset(&self) {
+                            self.a.reset();
+                            for update in &self.__visibility {
+                                update();
+                            }
+                        }
+
+                        fn set_disabled(&self, disabled: bool) {
+                            self.a.set_disabled(disabled);
+                        }
+
+                        fn get_raw(&self) -> rooting_forms::RawState {
+                            return rooting_forms::RawState::List(vec![self.a.get_raw()]);
+                        }
+
+                        fn set_raw(&self, raw: &rooting_forms::RawState) {
+                            let rooting_forms:: RawState:: List(raw) = raw else {
+                                return;
+                            };
+                            if raw.len() != 1usize {
+                                return;
+                            }
+                            self.a.set_raw(&raw[0usize]);
+                            for update in &self.__visibility {
+                                update();
+                            }
+                        }
+
+                        fn field(&self, path: &[&str]) -> Option<rooting_forms::FieldHandle> {
+                            let Some((first, path)) = path.split_first() else {
+                                return None;
+                            };
+                            match *first {
+                                "a" => return self.a.field(path),
+                                _ => return None,
+                            }
+                        }
+
+                        fn visit_fields<
+                            'a,
+                        >(&'a self, path: &mut Vec<&'a str>, f: &mut dyn FnMut(&[&'a str], rooting_forms::FieldHandle)) {
+                            path.push("a");
+                            self.a.visit_fields(path, f);
+                            path.pop();
+                        }
+
+                        fn visit_errors<
+                            'a,
+                        >(&'a self, path: &mut Vec<&'a str>, f: &mut dyn FnMut(&[&'a str], rooting_forms::FieldHandle)) {
+                            path.push("a");
+                            self.a.visit_errors(path, f);
+                            path.pop();
                         }
                     }
 
-                    Box::new(FormStateImpl { a: i32::new_form("A") })
+                    let mut state = FormStateImpl {
+                        a: std::rc::Rc::from(<i32 as rooting_forms::Form>::new_form_with("A", from.map(|from| &from.a))),
+                        __elements: vec![],
+                        __visibility: vec![],
+                    };
+                    {
+                        let mut elements = vec![];
+                        let subelements = state.a.elements();
+                        if <i32 as rooting_forms::Form>::FIELDSET {
+                            elements.push(
+                                rooting_forms::dom::el("fieldset")
+                                    .classes(&[rooting_forms::CSS_CLASS_SUBFORM])
+                                    .push(rooting_forms::dom::el("legend").text("A"))
+                                    .extend(subelements.error.into_iter().collect())
+                                    .extend(subelements.elements),
+                            );
+                        } else {
+                            elements.extend(subelements.error.into_iter());
+                            elements.push(
+                                rooting_forms::dom::el("span").classes(&[rooting_forms::CSS_CLASS_LABEL]).text("A"),
+                            );
+                            elements.extend(subelements.elements);
+                        }
+                        state.__elements.extend(elements);
+                    }
+                    Box::new(state)
+                }
+
+                fn display(_field: &str, value: &Self) -> Vec<rooting_forms::dom::El> {
+                    return {
+                        let mut elements: Vec<rooting_forms::dom::El> = vec![];
+                        {
+                            let subelements = <i32 as rooting_forms::Form>::display("A", &value.a);
+                            if <i32 as rooting_forms::Form>::FIELDSET {
+                                elements.push(
+                                    rooting_forms::dom::el("fieldset")
+                                        .classes(&[rooting_forms::CSS_CLASS_SUBFORM])
+                                        .push(rooting_forms::dom::el("legend").text("A"))
+                                        .extend(subelements),
+                                );
+                            } else {
+                                elements.push(
+                                    rooting_forms::dom::el("span").classes(&[rooting_forms::CSS_CLASS_LABEL]).text("A"),
+                                );
+                                elements.extend(subelements);
+                            }
+                        }
+                        elements
+                    };
+                }
+
+                fn schema(title: &str) -> rooting_forms::serde_json::Value {
+                    return rooting_forms::recursive_schema("Alpha", title, || {
+                        let mut properties = rooting_forms::serde_json::Map::new();
+                        let mut required: Vec<rooting_forms::serde_json::Value> = vec![];
+                        properties.insert("a".to_string(), <i32 as rooting_forms::Form>::schema("A"));
+                        if !<i32 as rooting_forms::Form>::OPTIONAL {
+                            required.push(rooting_forms::serde_json::Value::from("a"));
+                        }
+                        rooting_forms::serde_json::json!({
+                            "type": "object",
+                            "title": title,
+                            "properties": properties,
+                            "required": required,
+                            "additionalProperties": false
+                        })
+                    });
                 }
             }
         ));
@@ -440,143 +1667,259 @@ enum Alpha {
 "#,
             quote!{
                 impl rooting_forms::Form for Alpha {
-                    fn new_form(field: &str) -> Box<dyn rooting_forms::FormState<Self>> {
+                    fn new_form_with(field: &str, from: Option<&Self>) -> Box<dyn rooting_forms::FormState<Self>> {
                         use rooting_forms::FormState;
-                        use std::str::FromStr;
-                        use wasm_bindgen::JsCast;
-
-                        struct FormStateImpl {
-                            select: rooting::El,
-                            variant_parse: Vec<Box<dyn Fn() -> Result<Alpha, ()>>>,
-                            variant_elements: Vec<rooting::El>,
-                            current_variant: std::rc::Rc<std::cell::Cell<usize>>,
-                        }
 
-                        impl rooting_forms::FormState<Alpha> for FormStateImpl {
-                            fn elements(&self) -> rooting_forms::FormElements {
-                                let mut out = vec![];
-                                out.push(self.select.clone());
-                                out.extend(self.variant_elements.clone());
-                                return rooting_forms::FormElements {
-                                    error: None,
-                                    elements: out,
-                                };
+                        let initial_variant = match from {
+                            Some(Alpha::A { .. }) => 0usize,
+                            Some(Alpha::B { .. }) => 1usize,
+                            Some(Alpha::C { .. }) => 2usize,
+                            None => 0,
+                        };
+                        let mut variants: Vec<rooting_forms::EnumVariant<Alpha>> = vec![];
+                        variants.push(
+                            rooting_forms::EnumVariant::new(
+                                "A",
+                                "A",
+                                initial_variant == 0usize,
+                                from,
+                                |from| rooting_forms::UnitFormState::new(|| Alpha::A),
+                            ),
+                        );
+                        variants.push(
+                            rooting_forms::EnumVariant::new(
+                                "B",
+                                "B",
+                                initial_variant == 1usize,
+                                from,
+                                |from| rooting_forms::MapFormState::new(<i32 as rooting_forms::Form>::new_form_with("B", match from {
+                                    Some(Alpha::B(v)) => Some(v),
+                                    _ => None,
+                                }), |v| Alpha::B(v)),
+                            ),
+                        );
+                        variants.push(rooting_forms::EnumVariant::new("C", "C", initial_variant == 2usize, from, |from| {
+                            #[allow(non_camel_case_types)]
+                            struct Alpha_C_FormState {
+                                c: std::rc::Rc<dyn rooting_forms::FormState<i32>>,
+                                __elements: Vec<rooting_forms::dom::El>,
+                                __visibility: Vec<std::rc::Rc<dyn Fn()>>,
                             }
 
-                            fn parse(&self) -> Result<Alpha, ()> {
-                                return self.variant_parse[self.current_variant.get()]();
-                            }
-                        }
+                            impl rooting_forms::FormState<Alpha> for Alpha_C_FormState {
+                                fn elements(&self) -> rooting_forms::FormElements {
+                                    return rooting_forms::FormElements {
+                                        error: None,
+                                        elements: self.__elements.clone(),
+                                    };
+                                }
 
-                        let variant = std::rc::Rc::new(std::cell::Cell::new(0));
-                        let mut elements = vec![];
-                        let select =
-                            rooting::el("select")
-                                .classes(&[rooting_forms::CSS_CLASS_SMALL_INPUT])
-                                .attr(rooting_forms::ATTR_LABEL, field);
-                        elements.push(select.clone());
-                        let mut variant_parse: Vec<Box<dyn Fn() -> Result<Alpha, ()>>> = vec![];
-                        let mut variant_elements = vec![];
-                        {
-                            select.ref_push(
-                                rooting::el("option").text("A").attr("value", "0").attr("selected", "selected"),
-                            );
-                            variant_parse.push(Box::new(|| Ok(Alpha::A)));
-                            variant_elements.push(rooting::el("div").classes(&[rooting_forms::CSS_CLASS_SUBFORM]));
-                        }
-                        {
-                            select.ref_push(rooting::el("option").text("B").attr("value", "1"));
-                            let subform = i32::new_form("B");
-                            let subform_elements = subform.elements();
-                            let container = rooting::el("div").classes(&[rooting_forms::CSS_CLASS_SUBFORM]);
-                            if let Some(error) = subform_elements.error {
-                                container.ref_push(error);
-                            }
-                            container.ref_extend(subform_elements.elements);
-                            variant_elements.push(container);
-                            variant_parse.push(Box::new(move || subform.parse()));
-                        }
-                        {
-                            select.ref_push(rooting::el("option").text("C").attr("value", "2"));
-                            let subform = {
-                                #[allow(non_camel_case_types)]
-                                struct Alpha_C_FormState {
-                                    c: Box<dyn rooting_forms::FormState<i32>>,
+                                fn parse(&self) -> Result<Alpha, ()> {
+                                    let mut errored = false;
+                                    let c = match self.c.parse() {
+                                        Ok(v) => Some(v),
+                                        Err(e) => {
+                                            errored = true;
+                                            None
+                                        },
+                                    };
+                                    if errored {
+                                        return Err(());
+                                    }
+                                    return Ok(Alpha::C { c: c.unwrap() });
                                 }
 
-                                impl rooting_forms::FormState<Alpha> for Alpha_C_FormState {
-                                    fn elements(&self) -> rooting_forms::FormElements {
-                                        let mut elements = Vec::new();
-                                        {
-                                            let subelements = self.c.elements();
-                                            elements.extend(subelements.error.into_iter());
-                                            elements.push(
-                                                rooting::el("span")
-                                                    .classes(&[rooting_forms::CSS_CLASS_LABEL])
-                                                    .text("C"),
-                                            );
-                                            elements.extend(subelements.elements);
-                                        }
-                                        return rooting_forms::FormElements {
-                                            error: None,
-                                            elements: elements,
-                                        };
+                                fn peek(&self) -> Result<Alpha, ()> {
+                                    let mut errored = false;
+                                    let c = match self.c.peek() {
+                                        Ok(v) => Some(v),
+                                        Err(e) => {
+                                            errored = true;
+                                            None
+                                        },
+                                    };
+                                    if errored {
+                                        return Err(());
                                     }
+                                    return Ok(Alpha::C { c: c.unwrap() });
+                                }
+
+                                fn on_change(&self, cb: std::rc::Rc<dyn Fn()>) {
+                                    self.c.on_change(cb.clone());
+                                }
 
-                                    fn parse(&self) -> Result<Alpha, ()> {
-                                        let mut errored = false;
-                                        let c = match self.c.parse() {
-                                            Ok(v) => Some(v),
-                                            Err(e) => {
-                                                errored = true;
-                                                None
-                                            },
-                                        };
-                                        if errored {
-                                            return Err(());
-                                        }
-                                        return Ok(Alpha::C { c: c.unwrap() });
+                                fn is_dirty(&self) -> bool {
+                                    if self.c.is_dirty() {
+                                        return true;
                                     }
+                                    return false;
                                 }
 
-                                Box::new(FormStateImpl { c: i32::new_form("C") })
-                            };
-                            let subform_elements = subform.elements();
-                            let container = rooting::el("div").classes(&[rooting_forms::CSS_CLASS_SUBFORM]);
-                            if let Some(error) = subform_elements.error {
-                                container.ref_push(error);
+                                fn reset(&self) {
+                                    self.c.reset();
+                                    for update in &self.__visibility {
+                                        update();
+                                    }
+                                }
+
+                                fn set_disabled(&self, disabled: bool) {
+                                    self.c.set_disabled(disabled);
+                                }
+
+                                fn get_raw(&self) -> rooting_forms::RawState {
+                                    return rooting_forms::RawState::List(vec![self.c.get_raw()]);
+                                }
+
+                                fn set_raw(&self, raw: &rooting_forms::RawState) {
+                                    let rooting_forms:: RawState:: List(raw) = raw else {
+                                        return;
+                                    };
+                                    if raw.len() != 1usize {
+                                        return;
+                                    }
+                                    self.c.set_raw(&raw[0usize]);
+                                    for update in &self.__visibility {
+                                        update();
+                                    }
+                                }
+
+                                fn field(&self, path: &[&str]) -> Option<rooting_forms::FieldHandle> {
+                                    let Some((first, path)) = path.split_first() else {
+                                        return None;
+                                    };
+                                    match *first {
+                                        "c" => return self.c.field(path),
+                                        _ => return None,
+                                    }
+                                }
+
+                                fn visit_fields<
+                                    'a,
+                                >(&'a self, path: &mut Vec<&'a str>, f: &mut dyn FnMut(&[&'a str], rooting_forms::FieldHandle)) {
+                                    path.push("c");
+                                    self.c.visit_fields(path, f);
+                                    path.pop();
+                                }
+
+                                fn visit_errors<
+                                    'a,
+                                >(&'a self, path: &mut Vec<&'a str>, f: &mut dyn FnMut(&[&'a str], rooting_forms::FieldHandle)) {
+                                    path.push("c");
+                                    self.c.visit_errors(path, f);
+                                    path.pop();
+                                }
                             }
-                            container.ref_extend(subform_elements.elements);
-                            variant_elements.push(container);
-                            variant_parse.push(Box::new(move || subform.parse()));
-                        }
-                        select.ref_on("change", {
-                            let variant_elements = variant_elements.clone();
-                            let variant = variant.clone();
-                            move |event| {
-                                let index =
-                                    usize::from_str(
-                                        &event
-                                            .target()
-                                            .unwrap()
-                                            .dyn_into::<rooting_forms::republish::HtmlSelectElement>()
-                                            .unwrap()
-                                            .value(),
-                                    ).unwrap();
-                                variant.set(index);
-                                for (e_index, v) in variant_elements.iter().enumerate() {
-                                    v.ref_modify_classes(&[(rooting_forms::CSS_CLASS_HIDDEN, e_index != index)]);
+
+                            let mut state = Alpha_C_FormState {
+                                c: std::rc::Rc::from(<i32 as rooting_forms::Form>::new_form_with("C", match from {
+                                    Some(Alpha::C { c: v, .. }) => Some(v),
+                                    _ => None,
+                                })),
+                                __elements: vec![],
+                                __visibility: vec![],
+                            };
+                            {
+                                let mut elements = vec![];
+                                let subelements = state.c.elements();
+                                if <i32 as rooting_forms::Form>::FIELDSET {
+                                    elements.push(
+                                        rooting_forms::dom::el("fieldset")
+                                            .classes(&[rooting_forms::CSS_CLASS_SUBFORM])
+                                            .push(rooting_forms::dom::el("legend").text("C"))
+                                            .extend(subelements.error.into_iter().collect())
+                                            .extend(subelements.elements),
+                                    );
+                                } else {
+                                    elements.extend(subelements.error.into_iter());
+                                    elements.push(
+                                        rooting_forms::dom::el("span").classes(&[rooting_forms::CSS_CLASS_LABEL]).text("C"),
+                                    );
+                                    elements.extend(subelements.elements);
                                 }
+                                state.__elements.extend(elements);
                             }
-                        });
-                        for v in &variant_elements[1..] {
-                            v.ref_classes(&[rooting_forms::CSS_CLASS_HIDDEN]);
-                        }
-                        return Box::new(FormStateImpl {
-                            select: select,
-                            variant_parse: variant_parse,
-                            variant_elements: variant_elements,
-                            current_variant: variant,
+                            Box::new(state)
+                        }));
+                        return rooting_forms::EnumFormState::new(field, initial_variant, variants).unwrap();
+                    }
+
+                    fn display(field: &str, value: &Self) -> Vec<rooting_forms::dom::El> {
+                        let (title, subelements) = match value {
+                            Alpha::A => ("A", vec![]),
+                            Alpha::B(v) => ("B", <i32 as rooting_forms::Form>::display("B", v)),
+                            Alpha::C { c: v_c, .. } => ("C", {
+                                let mut elements: Vec<rooting_forms::dom::El> = vec![];
+                                {
+                                    let subelements = <i32 as rooting_forms::Form>::display("C", v_c);
+                                    if <i32 as rooting_forms::Form>::FIELDSET {
+                                        elements.push(
+                                            rooting_forms::dom::el("fieldset")
+                                                .classes(&[rooting_forms::CSS_CLASS_SUBFORM])
+                                                .push(rooting_forms::dom::el("legend").text("C"))
+                                                .extend(subelements),
+                                        );
+                                    } else {
+                                        elements.push(
+                                            rooting_forms::dom::el("span").classes(&[rooting_forms::CSS_CLASS_LABEL]).text("C"),
+                                        );
+                                        elements.extend(subelements);
+                                    }
+                                }
+                                elements
+                            }),
+                        };
+                        return vec![
+                            rooting_forms::display_text(field, title),
+                            rooting_forms::dom::el("div").classes(&[rooting_forms::CSS_CLASS_SUBFORM]).extend(subelements)
+                        ];
+                    }
+
+                    fn schema(title: &str) -> rooting_forms::serde_json::Value {
+                        return rooting_forms::recursive_schema("Alpha", title, || {
+                            let variants = vec![rooting_forms::serde_json::json!({
+                                "title": "A",
+                                "const": "A"
+                            }), {
+                                let value_schema = <i32 as rooting_forms::Form>::schema("B");
+                                rooting_forms::serde_json::json!({
+                                    "type": "object",
+                                    "title": "B",
+                                    "properties": {
+                                        "B": value_schema
+                                    },
+                                    "required":["B"],
+                                    "additionalProperties": false
+                                })
+                            }, {
+                                let fields_schema = {
+                                    let mut properties = rooting_forms::serde_json::Map::new();
+                                    let mut required: Vec<rooting_forms::serde_json::Value> = vec![];
+                                    properties.insert("c".to_string(), <i32 as rooting_forms::Form>::schema("C"));
+                                    if !<i32 as rooting_forms::Form>::OPTIONAL {
+                                        required.push(rooting_forms::serde_json::Value::from("c"));
+                                    }
+                                    rooting_forms::serde_json::json!({
+                                        "type": "object",
+                                        "title": "C",
+                                        "properties": properties,
+                                        "required": required,
+                                        "additionalProperties": false
+                                    })
+                                };
+                                rooting_forms::serde_json::json!({
+                                    "type": "object",
+                                    "title": "C",
+                                    "properties": {
+                                        "C": fields_schema
+                                    },
+                                    "required":["C"],
+                                    "additionalProperties": false
+                                })
+                            }];
+                            rooting_forms::serde_json::json!({
+                                "title": title,
+                                "oneOf": variants
+                            })
                         });
                     }
                 }
@@ -0,0 +1,471 @@
+use std::{
+    cell::{
+        Cell,
+        RefCell,
+    },
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    rc::{
+        Rc,
+        Weak,
+    },
+    sync::Arc,
+    task::{
+        Context,
+        Wake,
+        Waker,
+    },
+    thread::ThreadId,
+};
+
+/// An event passed to listeners registered with `El::on`.
+pub struct Event {
+    type_: String,
+    default_prevented: Cell<bool>,
+}
+
+impl Event {
+    pub fn new(type_: &str) -> Event {
+        return Event {
+            type_: type_.to_string(),
+            default_prevented: Cell::new(false),
+        };
+    }
+
+    pub fn type_(&self) -> String {
+        return self.type_.clone();
+    }
+
+    pub fn prevent_default(&self) {
+        self.default_prevented.set(true);
+    }
+
+    pub fn default_prevented(&self) -> bool {
+        return self.default_prevented.get();
+    }
+}
+
+type Listener = Rc<RefCell<Box<dyn FnMut(&Event)>>>;
+
+struct El_ {
+    tag: String,
+    attrs: Vec<(String, String)>,
+    classes: Vec<String>,
+    text: Option<String>,
+    children: Vec<El>,
+    listeners: Vec<(&'static str, Listener)>,
+    value: Option<String>,
+    checked: Option<bool>,
+}
+
+/// An in-memory element with the same construction methods as `rooting::El`, plus
+/// methods for inspecting the tree.
+#[derive(Clone)]
+pub struct El(Rc<RefCell<El_>>);
+
+impl El {
+    pub fn text(self, text: &str) -> Self {
+        self.ref_text(text);
+        return self;
+    }
+
+    pub fn ref_text(&self, text: &str) -> &Self {
+        let mut s = self.0.borrow_mut();
+        s.children.clear();
+        s.text = Some(text.to_string());
+        return self;
+    }
+
+    pub fn attr(self, key: &str, value: &str) -> Self {
+        self.ref_attr(key, value);
+        return self;
+    }
+
+    pub fn ref_attr(&self, key: &str, value: &str) -> &Self {
+        let mut s = self.0.borrow_mut();
+        match s.attrs.iter_mut().find(|(k, _)| k == key) {
+            Some((_, v)) => *v = value.to_string(),
+            None => s.attrs.push((key.to_string(), value.to_string())),
+        }
+        return self;
+    }
+
+    pub fn ref_remove_attr(&self, key: &str) -> &Self {
+        self.0.borrow_mut().attrs.retain(|(k, _)| k != key);
+        return self;
+    }
+
+    pub fn classes(self, keys: &[&str]) -> Self {
+        self.ref_classes(keys);
+        return self;
+    }
+
+    pub fn ref_classes(&self, keys: &[&str]) -> &Self {
+        return self.ref_modify_classes(&keys.iter().map(|k| (*k, true)).collect::<Vec<_>>());
+    }
+
+    pub fn ref_remove_classes(&self, keys: &[&str]) -> &Self {
+        return self.ref_modify_classes(&keys.iter().map(|k| (*k, false)).collect::<Vec<_>>());
+    }
+
+    pub fn ref_modify_classes(&self, keys: &[(&str, bool)]) -> &Self {
+        let mut s = self.0.borrow_mut();
+        for (k, on) in keys {
+            let has = s.classes.iter().any(|c| c == k);
+            if *on && !has {
+                s.classes.push(k.to_string());
+            } else if !*on && has {
+                s.classes.retain(|c| c != k);
+            }
+        }
+        return self;
+    }
+
+    pub fn push(self, add: El) -> Self {
+        self.ref_push(add);
+        return self;
+    }
+
+    pub fn ref_push(&self, add: El) -> &Self {
+        let mut s = self.0.borrow_mut();
+        s.text = None;
+        s.children.push(add);
+        return self;
+    }
+
+    pub fn extend(self, add: Vec<El>) -> Self {
+        self.ref_extend(add);
+        return self;
+    }
+
+    pub fn ref_extend(&self, add: Vec<El>) -> &Self {
+        let mut s = self.0.borrow_mut();
+        s.text = None;
+        s.children.extend(add);
+        return self;
+    }
+
+    pub fn ref_clear(&self) -> &Self {
+        let mut s = self.0.borrow_mut();
+        s.text = None;
+        s.children.clear();
+        return self;
+    }
+
+    pub fn on(self, event: &'static str, cb: impl FnMut(&Event) + 'static) -> Self {
+        self.ref_on(event, cb);
+        return self;
+    }
+
+    pub fn ref_on(&self, event: &'static str, cb: impl FnMut(&Event) + 'static) -> &Self {
+        self.0.borrow_mut().listeners.push((event, Rc::new(RefCell::new(Box::new(cb)))));
+        return self;
+    }
+
+    /// Produce a weak reference to the element.
+    pub fn weak(&self) -> WeakEl {
+        return WeakEl(Rc::downgrade(&self.0));
+    }
+
+    /// The element tag, ex: `input`.
+    pub fn get_tag(&self) -> String {
+        return self.0.borrow().tag.clone();
+    }
+
+    pub fn get_attr(&self, key: &str) -> Option<String> {
+        return self.0.borrow().attrs.iter().find(|(k, _)| k == key).map(|(_, v)| v.clone());
+    }
+
+    pub fn has_class(&self, key: &str) -> bool {
+        return self.0.borrow().classes.iter().any(|c| c == key);
+    }
+
+    pub fn get_children(&self) -> Vec<El> {
+        return self.0.borrow().children.clone();
+    }
+
+    /// The text content of the element and its descendants.
+    pub fn get_text(&self) -> String {
+        let s = self.0.borrow();
+        if let Some(t) = &s.text {
+            return t.clone();
+        }
+        return s.children.iter().map(|c| c.get_text()).collect();
+    }
+
+    /// Serialize the element and its descendants as HTML, including the current
+    /// values and checked states of inputs.
+    pub fn to_html(&self) -> String {
+        let mut out = String::new();
+        self.write_html(&mut out, None);
+        return out;
+    }
+
+    fn write_html(&self, out: &mut String, selected: Option<&str>) {
+        let s = self.0.borrow();
+        let mut attrs = s.attrs.clone();
+        let mut set_attr = |key: &str, value: Option<String>| {
+            attrs.retain(|(k, _)| k != key);
+            if let Some(value) = value {
+                attrs.push((key.to_string(), value));
+            }
+        };
+        match s.tag.as_str() {
+            "input" => {
+                if let Some(v) = &s.value {
+                    set_attr("value", Some(v.clone()));
+                }
+                if let Some(c) = s.checked {
+                    set_attr("checked", c.then(|| "checked".to_string()));
+                }
+            },
+            "option" => {
+                if let Some(selected) = selected {
+                    let value = s.attrs.iter().find(|(k, _)| k == "value").map(|(_, v)| v.clone());
+                    set_attr(
+                        "selected",
+                        (value.as_deref() == Some(selected)).then(|| "selected".to_string()),
+                    );
+                }
+            },
+            _ => { },
+        }
+        if !s.classes.is_empty() {
+            attrs.insert(0, ("class".to_string(), s.classes.join(" ")));
+        }
+        out.push('<');
+        out.push_str(&s.tag);
+        for (k, v) in &attrs {
+            out.push_str(&format!(" {}=\"{}\"", k, escape_html(v)));
+        }
+        out.push('>');
+        if ["input", "br", "hr", "img", "meta", "link"].contains(&s.tag.as_str()) {
+            return;
+        }
+        if s.tag == "textarea" && s.value.is_some() {
+            out.push_str(&escape_html(s.value.as_ref().unwrap()));
+        } else if let Some(t) = &s.text {
+            out.push_str(&escape_html(t));
+        } else {
+            let selected = if s.tag == "select" {
+                s.value.as_deref()
+            } else {
+                None
+            };
+            for c in &s.children {
+                c.write_html(out, selected);
+            }
+        }
+        out.push_str(&format!("</{}>", s.tag));
+    }
+
+    /// Call the listeners for `event.type_()` on this element (there's no bubbling).
+    pub fn dispatch(&self, event: &Event) {
+        let listeners =
+            self
+                .0
+                .borrow()
+                .listeners
+                .iter()
+                .filter(|(k, _)| *k == event.type_)
+                .map(|(_, l)| l.clone())
+                .collect::<Vec<_>>();
+        for l in listeners {
+            (l.borrow_mut())(event);
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct WeakEl(Weak<RefCell<El_>>);
+
+impl WeakEl {
+    pub fn upgrade(&self) -> Option<El> {
+        return Some(El(self.0.upgrade()?));
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    return text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;");
+}
+
+/// Create a new element.
+pub fn el(tag: &str) -> El {
+    return El(Rc::new(RefCell::new(El_ {
+        tag: tag.to_string(),
+        attrs: vec![],
+        classes: vec![],
+        text: None,
+        children: vec![],
+        listeners: vec![],
+        value: None,
+        checked: None,
+    })));
+}
+
+pub fn value(el: &El) -> String {
+    let s = el.0.borrow();
+    if let Some(v) = &s.value {
+        return v.clone();
+    }
+    match s.tag.as_str() {
+        "textarea" => return el.get_text(),
+        "select" => {
+            let options = s.children.iter().filter(|c| c.get_tag() == "option").collect::<Vec<_>>();
+            return options
+                .iter()
+                .find(|o| o.get_attr("selected").is_some())
+                .or(options.first())
+                .map(|o| o.get_attr("value").unwrap_or_else(|| o.get_text()))
+                .unwrap_or_default();
+        },
+        _ => return el.get_attr("value").unwrap_or_default(),
+    }
+}
+
+pub fn set_value(el: &El, value: &str) {
+    el.0.borrow_mut().value = Some(value.to_string());
+}
+
+pub fn checked(el: &El) -> bool {
+    if let Some(c) = el.0.borrow().checked {
+        return c;
+    }
+    return el.get_attr("checked").is_some();
+}
+
+pub fn set_checked(el: &El, checked: bool) {
+    el.0.borrow_mut().checked = Some(checked);
+}
+
+pub fn attr(el: &El, key: &str) -> Option<String> {
+    return el.get_attr(key);
+}
+
+pub fn disabled(el: &El) -> bool {
+    return el.get_attr("disabled").is_some();
+}
+
+thread_local!{
+    static FOCUSED: RefCell<Option<WeakEl>> = RefCell::new(None);
+}
+
+pub fn focus(el: &El) {
+    FOCUSED.with(|f| *f.borrow_mut() = Some(el.weak()));
+    el.dispatch(&Event::new("focus"));
+}
+
+/// The element most recently passed to `focus`, if it still exists.
+pub fn focused() -> Option<El> {
+    return FOCUSED.with(|f| f.borrow().as_ref().and_then(|f| f.upgrade()));
+}
+
+pub fn scroll_into_view(_el: &El) { }
+
+pub fn text(el: &El) -> String {
+    return el.get_text();
+}
+
+pub fn options(el: &El) -> Vec<(String, String)> {
+    return el
+        .get_children()
+        .iter()
+        .filter(|o| o.get_tag() == "option")
+        .map(|o| (o.get_attr("value").unwrap_or_else(|| o.get_text()), o.get_text()))
+        .collect();
+}
+
+pub fn dispatch(el: &El, event: &str) {
+    el.dispatch(&Event::new(event));
+}
+
+thread_local!{
+    static STORAGE: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+}
+
+pub fn storage_get(key: &str) -> Option<String> {
+    return STORAGE.with(|s| s.borrow().get(key).cloned());
+}
+
+pub fn storage_set(key: &str, value: &str) {
+    STORAGE.with(|s| s.borrow_mut().insert(key.to_string(), value.to_string()));
+}
+
+pub fn storage_remove(key: &str) {
+    STORAGE.with(|s| s.borrow_mut().remove(key));
+}
+
+struct Task {
+    /// Taken while the task is being polled.
+    future: Option<Pin<Box<dyn Future<Output = ()>>>>,
+    /// Woken while being polled, so it needs to be polled again.
+    woken: bool,
+}
+
+thread_local!{
+    static TASKS: RefCell<HashMap<usize, Task>> = RefCell::new(HashMap::new());
+    static NEXT_TASK: Cell<usize> = Cell::new(0);
+}
+
+struct TaskWaker {
+    id: usize,
+    thread: ThreadId,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        // Tasks live in thread-local storage, so they can only be resumed from the
+        // spawning thread
+        if std::thread::current().id() == self.thread {
+            poll_task(self.id);
+        }
+    }
+}
+
+fn poll_task(id: usize) {
+    loop {
+        let future = TASKS.with(|t| {
+            let mut t = t.borrow_mut();
+            let task = t.get_mut(&id)?;
+            let future = task.future.take();
+            if future.is_none() {
+                task.woken = true;
+            }
+            return future;
+        });
+        let Some(mut future) = future else {
+            return;
+        };
+        let waker = Waker::from(Arc::new(TaskWaker {
+            id: id,
+            thread: std::thread::current().id(),
+        }));
+        let done = future.as_mut().poll(&mut Context::from_waker(&waker)).is_ready();
+        let again = TASKS.with(|t| {
+            let mut t = t.borrow_mut();
+            if done {
+                t.remove(&id);
+                return false;
+            }
+            let task = t.get_mut(&id).unwrap();
+            task.future = Some(future);
+            return std::mem::replace(&mut task.woken, false);
+        });
+        if !again {
+            return;
+        }
+    }
+}
+
+/// Runs the future until it's pending, then again each time it's woken (which
+/// must happen on the same thread). Futures that never wait complete before this
+/// returns.
+pub fn spawn(f: impl Future<Output = ()> + 'static) {
+    let id = NEXT_TASK.with(|n| n.replace(n.get() + 1));
+    TASKS.with(|t| t.borrow_mut().insert(id, Task {
+        future: Some(Box::pin(f)),
+        woken: false,
+    }));
+    poll_task(id);
+}
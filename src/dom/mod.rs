@@ -0,0 +1,33 @@
+//! The DOM operations used by forms. In the browser (`wasm32`) `El` is a
+//! `rooting::El` and these operate on the real DOM. Elsewhere `El` is an in-memory
+//! element, so forms can be built, filled and parsed in regular `cargo test`s.
+//!
+//! * `value`, `set_value` - the current value of an `<input>`, `<select>` or
+//!   `<textarea>`
+//!
+//! * `checked`, `set_checked` - the checked state of a checkbox
+//!
+//! * `attr` - the value of an attribute, ex: `aria-label`
+//!
+//! * `disabled` - whether an input has the `disabled` attribute
+//!
+//! * `focus`, `scroll_into_view` - move the user's attention to an element
+//!
+//! * `text` - the text content of an element and its descendants
+//!
+//! * `options` - the `(value, text)` of each `<option>` in a `<select>`
+//!
+//! * `dispatch` - fire an event (ex: `input`, `change`) at an element
+//!
+//! * `storage_get`, `storage_set`, `storage_remove` - `localStorage` (a
+//!   thread-local map in memory)
+//!
+//! * `spawn` - run a future (in memory, on the current thread as it's woken)
+#[cfg(target_arch = "wasm32")]
+mod web;
+#[cfg(target_arch = "wasm32")]
+pub use web::*;
+#[cfg(not(target_arch = "wasm32"))]
+mod headless;
+#[cfg(not(target_arch = "wasm32"))]
+pub use headless::*;
@@ -0,0 +1,119 @@
+use std::future::Future;
+use wasm_bindgen::JsCast;
+use web_sys::{
+    HtmlElement,
+    HtmlInputElement,
+    HtmlSelectElement,
+    HtmlTextAreaElement,
+    Storage,
+};
+pub use rooting::{
+    El,
+    WeakEl,
+    el,
+};
+pub use web_sys::Event;
+
+pub fn value(el: &El) -> String {
+    let raw = el.raw();
+    if let Some(e) = raw.dyn_ref::<HtmlInputElement>() {
+        return e.value();
+    }
+    if let Some(e) = raw.dyn_ref::<HtmlSelectElement>() {
+        return e.value();
+    }
+    if let Some(e) = raw.dyn_ref::<HtmlTextAreaElement>() {
+        return e.value();
+    }
+    return String::new();
+}
+
+pub fn set_value(el: &El, value: &str) {
+    let raw = el.raw();
+    if let Some(e) = raw.dyn_ref::<HtmlInputElement>() {
+        e.set_value(value);
+    } else if let Some(e) = raw.dyn_ref::<HtmlSelectElement>() {
+        e.set_value(value);
+    } else if let Some(e) = raw.dyn_ref::<HtmlTextAreaElement>() {
+        e.set_value(value);
+    }
+}
+
+pub fn checked(el: &El) -> bool {
+    return el.raw().dyn_ref::<HtmlInputElement>().map(|e| e.checked()).unwrap_or(false);
+}
+
+pub fn set_checked(el: &El, checked: bool) {
+    if let Some(e) = el.raw().dyn_ref::<HtmlInputElement>() {
+        e.set_checked(checked);
+    }
+}
+
+pub fn attr(el: &El, key: &str) -> Option<String> {
+    return el.raw().get_attribute(key);
+}
+
+pub fn disabled(el: &El) -> bool {
+    return el.raw().has_attribute("disabled");
+}
+
+pub fn focus(el: &El) {
+    if let Some(e) = el.raw().dyn_ref::<HtmlElement>() {
+        _ = e.focus();
+    }
+}
+
+pub fn scroll_into_view(el: &El) {
+    el.raw().scroll_into_view();
+}
+
+pub fn text(el: &El) -> String {
+    return el.raw().text_content().unwrap_or_default();
+}
+
+pub fn options(el: &El) -> Vec<(String, String)> {
+    let children = el.raw().children();
+    let mut out = vec![];
+    for i in 0 .. children.length() {
+        let Some(o) = children.item(i) else {
+            continue;
+        };
+        if o.tag_name().to_lowercase() != "option" {
+            continue;
+        }
+        let text = o.text_content().unwrap_or_default();
+        out.push((o.get_attribute("value").unwrap_or_else(|| text.clone()), text));
+    }
+    return out;
+}
+
+pub fn dispatch(el: &El, event: &str) {
+    _ = el.raw().dispatch_event(&Event::new(event).unwrap());
+}
+
+fn storage() -> Option<Storage> {
+    return web_sys::window()?.local_storage().ok()?;
+}
+
+pub fn storage_get(key: &str) -> Option<String> {
+    return storage()?.get_item(key).ok()?;
+}
+
+pub fn storage_set(key: &str, value: &str) {
+    let Some(storage) = storage() else {
+        return;
+    };
+    _ = storage.set_item(key, value);
+}
+
+pub fn storage_remove(key: &str) {
+    let Some(storage) = storage() else {
+        return;
+    };
+    _ = storage.remove_item(key);
+}
+
+/// Runs the future on the browser event loop.
+pub fn spawn(f: impl Future<Output = ()> + 'static) {
+    wasm_bindgen_futures::spawn_local(f);
+}
@@ -0,0 +1,51 @@
+use std::rc::{
+    Rc,
+    Weak,
+};
+use crate::{
+    dom,
+    FormState,
+    RawState,
+};
+
+/// Saves the raw state of a form to `localStorage` after every change, so that
+/// input isn't lost if the page is reloaded.
+pub struct Draft {
+    key: String,
+}
+
+impl Draft {
+    /// Restore the form from the draft saved under `key`, if there is one, and start
+    /// saving changes to it.
+    pub fn new<T: 'static>(key: &str, state: &Rc<dyn FormState<T>>) -> Draft {
+        let draft = Draft { key: key.to_string() };
+        if let Some(raw) = draft.load() {
+            state.set_raw(&raw);
+        }
+        state.on_change(Rc::new({
+            let key = draft.key.clone();
+            let state: Weak<dyn FormState<T>> = Rc::downgrade(state);
+            move || {
+                let Some(state) = state.upgrade() else {
+                    return;
+                };
+                let Ok(text) = serde_json::to_string(&state.get_raw()) else {
+                    return;
+                };
+                dom::storage_set(&key, &text);
+            }
+        }));
+        return draft;
+    }
+
+    fn load(&self) -> Option<RawState> {
+        let text = dom::storage_get(&self.key)?;
+        return serde_json::from_str(&text).ok();
+    }
+
+    /// Delete the saved draft, ex: after the form was successfully submitted.
+    /// Further changes will be saved again.
+    pub fn clear(&self) {
+        dom::storage_remove(&self.key);
+    }
+}
@@ -0,0 +1,763 @@
+use std::{
+    any::Any,
+    cell::{
+        Cell,
+        OnceCell,
+        RefCell,
+    },
+    rc::{
+        Rc,
+        Weak,
+    },
+};
+use serde_json::{
+    Map,
+    Value,
+};
+use crate::{
+    dom::{
+        El,
+        el,
+    },
+    row_label,
+    BoolFormState,
+    CheckboxGroupFormState,
+    EnumFormState,
+    EnumVariant,
+    FieldHandle,
+    FormElements,
+    FormState,
+    FromStrFormState,
+    MapFormState,
+    OptionFormState,
+    RawState,
+    RowFormState,
+    UnitFormState,
+    ATTR_LABEL,
+    CSS_CLASS_ERROR,
+    CSS_CLASS_HIDDEN,
+    CSS_CLASS_LABEL,
+    CSS_CLASS_LIST,
+    CSS_CLASS_LIST_ADD,
+    CSS_CLASS_LIST_REMOVE,
+    CSS_CLASS_SUBFORM,
+};
+
+struct DynamicField {
+    name: String,
+    /// Not listed in `required`, omitted from the value if disabled.
+    optional: bool,
+    form: Box<dyn FormState<Value>>,
+}
+
+/// A form built at runtime from a JSON Schema, producing a `serde_json::Value`.
+/// This uses the same inputs as the static `Form` implementations:
+///
+/// * `string`, `integer`, `number`, `boolean` - a text input or checkbox
+///
+/// * `oneOf`, `anyOf`, `enum` - the enum variant `<select>`
+///
+/// * `null` alternatives (`"type": [..., "null"]` or `anyOf` with `{"type":
+///   "null"}`) and properties that aren't `required` - the `Option` checkbox
+///
+/// * `object` - the fields grouped like a nested struct
+///
+/// * `array` - with `prefixItems`, one input per element in a row like a tuple.
+///   With `uniqueItems` and `items` limited to constants, a checkbox per constant
+///   like `CheckboxGroup`. Otherwise a list of `items` inputs, with buttons to add
+///   and remove rows. `minItems` and `maxItems` are checked when parsing.
+///
+/// Titles come from `title`, falling back to the property name.
+pub struct DynamicForm {
+    fields: Vec<DynamicField>,
+    elements: Vec<El>,
+}
+
+fn schema_type(schema: &Value) -> Option<&str> {
+    return schema.get("type").and_then(|t| t.as_str());
+}
+
+fn is_null_schema(schema: &Value) -> bool {
+    return schema_type(schema) == Some("null");
+}
+
+fn is_nullable(schema: &Value) -> bool {
+    if let Some(alternatives) = schema.get("oneOf").or_else(|| schema.get("anyOf")).and_then(|a| a.as_array()) {
+        return alternatives.iter().any(is_null_schema);
+    }
+    if let Some(types) = schema.get("type").and_then(|t| t.as_array()) {
+        return types.iter().any(|t| t.as_str() == Some("null"));
+    }
+    return is_null_schema(schema);
+}
+
+/// A rough check for choosing the initial variant of a `oneOf` from an existing
+/// value.
+fn matches(schema: &Value, value: &Value) -> bool {
+    if let Some(c) = schema.get("const") {
+        return c == value;
+    }
+    if let Some(e) = schema.get("enum").and_then(|e| e.as_array()) {
+        return e.contains(value);
+    }
+    match schema_type(schema) {
+        Some("object") => {
+            let Some(value) = value.as_object() else {
+                return false;
+            };
+            return schema
+                .get("required")
+                .and_then(|r| r.as_array())
+                .map(|r| r.iter().filter_map(|k| k.as_str()).all(|k| value.contains_key(k)))
+                .unwrap_or(true);
+        },
+        Some("string") => return value.is_string(),
+        Some("integer") => return value.is_i64() || value.is_u64(),
+        Some("number") => return value.is_number(),
+        Some("boolean") => return value.is_boolean(),
+        Some("array") => return value.is_array(),
+        Some("null") => return value.is_null(),
+        _ => return true,
+    }
+}
+
+fn new_nullable(label: &str, schema: &Value, from: Option<&Value>) -> Result<Box<dyn FormState<Value>>, String> {
+    let from = from.filter(|v| !v.is_null());
+    return Ok(
+        MapFormState::new(
+            OptionFormState::new(label, DynamicForm::new(label, schema, from)?, from.is_some()),
+            |v| v.unwrap_or(Value::Null),
+        ),
+    );
+}
+
+fn new_variants(
+    label: &str,
+    schemas: &Vec<Value>,
+    from: Option<&Value>,
+) -> Result<Box<dyn FormState<Value>>, String> {
+    let mut initial_variant = None;
+    let mut variants = vec![];
+    for (i, schema) in schemas.iter().enumerate() {
+        let from = from.filter(|v| matches(schema, v));
+        if from.is_some() && initial_variant.is_none() {
+            initial_variant = Some(i);
+        }
+        let const_name = schema.get("const").and_then(|c| c.as_str());
+        let property_name =
+            schema
+                .get("required")
+                .and_then(|r| r.as_array())
+                .filter(|r| r.len() == 1)
+                .and_then(|r| r[0].as_str());
+        let title = schema.get("title").and_then(|t| t.as_str());
+        let name = const_name.or(property_name).or(title).map(|n| n.to_string()).unwrap_or_else(|| i.to_string());
+        let title = title.map(|t| t.to_string()).unwrap_or_else(|| name.clone());
+        // Built now rather than on selection so that schema errors are returned from
+        // `new`
+        let form = DynamicForm::new(&title, schema, from)?;
+        variants.push(EnumVariant {
+            form: Box::new(move || form),
+            name: name,
+            title: title,
+        });
+    }
+    return EnumFormState::new(label, initial_variant.unwrap_or(0), variants);
+}
+
+/// A row of a `DynamicList`. Rows are only appended, so that `visit_fields` can
+/// borrow their names, and removing a row shifts the later rows' inputs up instead.
+struct DynamicListRow {
+    /// The row index, for paths.
+    name: String,
+    form: Box<dyn FormState<Value>>,
+    el: El,
+    remove: El,
+    next: OnceCell<Box<DynamicListRow>>,
+}
+
+/// An array with a schema for `items`, with buttons to add and remove rows.
+struct DynamicList {
+    label: String,
+    build: Box<dyn Fn(&str, Option<&Value>) -> Result<Box<dyn FormState<Value>>, String>>,
+    el: El,
+    rows_el: El,
+    add: El,
+    error_el: El,
+    first: OnceCell<Box<DynamicListRow>>,
+    /// The number of rows shown, a prefix of the built rows.
+    len: Cell<usize>,
+    /// The raw state of a new row.
+    blank: RawState,
+    initial: OnceCell<RawState>,
+    min: usize,
+    max: usize,
+    on_change: RefCell<Vec<Rc<dyn Fn()>>>,
+    disabled: Cell<bool>,
+    /// The last `parse` failed.
+    invalid: Cell<bool>,
+    this: Weak<DynamicList>,
+}
+
+impl DynamicList {
+    fn new(label: &str, schema: &Value, from: Option<&Value>) -> Result<Rc<DynamicList>, String> {
+        let items = schema.get("items").cloned().unwrap_or(Value::Bool(true));
+        if !items.is_object() {
+            return Err(format!("Array schemas need an `items` schema or `prefixItems` ({})", label));
+        }
+        let build: Box<dyn Fn(&str, Option<&Value>) -> Result<Box<dyn FormState<Value>>, String>> =
+            Box::new(move |label, from| DynamicForm::new(label, &items, from));
+        let blank = build(label, None)?.get_raw();
+        let add = el("button").classes(&[CSS_CLASS_LIST_ADD]).attr("type", "button").text("+");
+        let rows_el = el("div");
+        let list = Rc::new_cyclic(|this| DynamicList {
+            label: label.to_string(),
+            build: build,
+            el: el("div").classes(&[CSS_CLASS_LIST]).attr(ATTR_LABEL, label).push(rows_el.clone()).push(add.clone()),
+            rows_el: rows_el,
+            add: add.clone(),
+            error_el: el("span").classes(&[CSS_CLASS_ERROR]),
+            first: OnceCell::new(),
+            len: Cell::new(0),
+            blank: blank,
+            initial: OnceCell::new(),
+            min: schema.get("minItems").and_then(|v| v.as_u64()).map(|v| v as usize).unwrap_or(0),
+            max: schema.get("maxItems").and_then(|v| v.as_u64()).map(|v| v as usize).unwrap_or(usize::MAX),
+            on_change: RefCell::new(vec![]),
+            disabled: Cell::new(false),
+            invalid: Cell::new(false),
+            this: this.clone(),
+        });
+        for v in from.and_then(|v| v.as_array()).into_iter().flatten() {
+            list.push_row(Some(v))?;
+        }
+        add.ref_on("click", {
+            let list = Rc::downgrade(&list);
+            move |_| {
+                let Some(list) = list.upgrade() else {
+                    return;
+                };
+                if list.push_row(None).is_ok() {
+                    list.changed();
+                }
+            }
+        });
+        let _ = list.initial.set(list.get_raw());
+        return Ok(list);
+    }
+
+    /// The shown rows.
+    fn rows(&self) -> impl Iterator<Item = &DynamicListRow> {
+        return std::iter::successors(self.first.get(), |r| r.next.get()).map(|r| r.as_ref()).take(self.len.get());
+    }
+
+    /// The row at `index`, building it if necessary.
+    fn row(&self, index: usize) -> Result<&DynamicListRow, String> {
+        let mut slot = &self.first;
+        for i in 0 ..= index {
+            if slot.get().is_none() {
+                let name = i.to_string();
+                let form = (self.build)(&row_label(&self.label, i), None)?;
+                for cb in self.on_change.borrow().iter() {
+                    form.on_change(cb.clone());
+                }
+                form.set_disabled(self.disabled.get());
+                let subelements = form.elements();
+                let remove =
+                    el("button").classes(&[CSS_CLASS_LIST_REMOVE]).attr("type", "button").text("-").on("click", {
+                        let list = self.this.clone();
+                        move |_| {
+                            let Some(list) = list.upgrade() else {
+                                return;
+                            };
+                            list.remove_row(i);
+                            list.changed();
+                        }
+                    });
+                if self.disabled.get() {
+                    remove.ref_attr("disabled", "disabled");
+                }
+                let row_el =
+                    el("div")
+                        .classes(&[CSS_CLASS_SUBFORM])
+                        .extend(subelements.error.into_iter().collect())
+                        .extend(subelements.elements)
+                        .push(remove.clone());
+                self.rows_el.ref_push(row_el.clone());
+                let _ = slot.set(Box::new(DynamicListRow {
+                    name: name,
+                    form: form,
+                    el: row_el,
+                    remove: remove,
+                    next: OnceCell::new(),
+                }));
+            }
+            let row = slot.get().unwrap();
+            if i == index {
+                return Ok(row);
+            }
+            slot = &row.next;
+        }
+        unreachable!();
+    }
+
+    /// Show another row, with the inputs set from `from` or blank.
+    fn push_row(&self, from: Option<&Value>) -> Result<(), String> {
+        let index = self.len.get();
+        let row = self.row(index)?;
+        match from {
+            Some(from) => row.form.set_raw(&(self.build)(&row_label(&self.label, index), Some(from))?.get_raw()),
+            None => row.form.set_raw(&self.blank),
+        }
+        row.el.ref_remove_classes(&[CSS_CLASS_HIDDEN]);
+        self.len.set(index + 1);
+        return Ok(());
+    }
+
+    fn remove_row(&self, index: usize) {
+        let mut raw = self.rows().map(|r| r.form.get_raw()).collect::<Vec<_>>();
+        if index >= raw.len() {
+            return;
+        }
+        raw.remove(index);
+        self.set_rows(&raw);
+    }
+
+    /// Show exactly `raw.len()` rows with the inputs set from `raw`.
+    fn set_rows(&self, raw: &[RawState]) {
+        for (i, raw) in raw.iter().enumerate() {
+            let Ok(row) = self.row(i) else {
+                return;
+            };
+            row.form.set_raw(raw);
+            row.el.ref_remove_classes(&[CSS_CLASS_HIDDEN]);
+        }
+        for row in self.rows().skip(raw.len()) {
+            row.form.set_raw(&self.blank);
+            row.el.ref_classes(&[CSS_CLASS_HIDDEN]);
+        }
+        self.len.set(raw.len());
+        self.error_el.ref_text("");
+        self.invalid.set(false);
+    }
+
+    fn changed(&self) {
+        for cb in self.on_change.borrow().clone() {
+            cb();
+        }
+    }
+
+    fn check_len(&self) -> Result<(), String> {
+        let len = self.len.get();
+        if len < self.min {
+            return Err(format!("Add at least {}", self.min));
+        }
+        if len > self.max {
+            return Err(format!("Add at most {}", self.max));
+        }
+        return Ok(());
+    }
+
+    fn parse_rows(&self, parse: impl Fn(&dyn FormState<Value>) -> Result<Value, ()>) -> Result<Value, ()> {
+        let mut out = vec![];
+        let mut errored = false;
+        for row in self.rows() {
+            match parse(row.form.as_ref()) {
+                Ok(v) => out.push(v),
+                Err(()) => errored = true,
+            }
+        }
+        if errored {
+            return Err(());
+        }
+        return Ok(Value::Array(out));
+    }
+}
+
+impl FormState<Value> for DynamicList {
+    fn elements(&self) -> FormElements {
+        return FormElements {
+            error: Some(self.error_el.clone()),
+            elements: vec![self.el.clone()],
+        };
+    }
+
+    fn parse(&self) -> Result<Value, ()> {
+        let len = self.check_len();
+        self.error_el.ref_text(len.as_ref().err().map(|e| e.as_str()).unwrap_or_default());
+        self.invalid.set(len.is_err());
+        let out = self.parse_rows(|f| f.parse());
+        len.map_err(|_| ())?;
+        return out;
+    }
+
+    fn peek(&self) -> Result<Value, ()> {
+        self.check_len().map_err(|_| ())?;
+        return self.parse_rows(|f| f.peek());
+    }
+
+    fn on_change(&self, cb: Rc<dyn Fn()>) {
+        for row in std::iter::successors(self.first.get(), |r| r.next.get()) {
+            row.form.on_change(cb.clone());
+        }
+        self.on_change.borrow_mut().push(cb);
+    }
+
+    fn is_dirty(&self) -> bool {
+        return Some(&self.get_raw()) != self.initial.get();
+    }
+
+    fn reset(&self) {
+        if let Some(initial) = self.initial.get() {
+            self.set_raw(initial);
+        }
+    }
+
+    fn set_disabled(&self, disabled: bool) {
+        self.disabled.set(disabled);
+        for row in std::iter::successors(self.first.get(), |r| r.next.get()) {
+            row.form.set_disabled(disabled);
+        }
+        let rows = std::iter::successors(self.first.get(), |r| r.next.get());
+        for button in std::iter::once(&self.add).chain(rows.map(|r| &r.remove)) {
+            if disabled {
+                button.ref_attr("disabled", "disabled");
+            } else {
+                button.ref_remove_attr("disabled");
+            }
+        }
+    }
+
+    fn get_raw(&self) -> RawState {
+        return RawState::List(self.rows().map(|r| r.form.get_raw()).collect());
+    }
+
+    fn set_raw(&self, raw: &RawState) {
+        let RawState::List(raw) = raw else {
+            return;
+        };
+        self.set_rows(raw);
+    }
+
+    fn field(&self, path: &[&str]) -> Option<FieldHandle> {
+        let (first, path) = path.split_first()?;
+        return self.rows().find(|r| r.name == *first)?.form.field(path);
+    }
+
+    fn visit_fields<'a>(&'a self, path: &mut Vec<&'a str>, f: &mut dyn FnMut(&[&'a str], FieldHandle)) {
+        for row in self.rows() {
+            path.push(&row.name);
+            row.form.visit_fields(path, f);
+            path.pop();
+        }
+    }
+
+    fn visit_errors<'a>(&'a self, path: &mut Vec<&'a str>, f: &mut dyn FnMut(&[&'a str], FieldHandle)) {
+        if self.invalid.get() {
+            f(path, FieldHandle {
+                input: self.add.clone(),
+                error: Some(self.error_el.clone()),
+                checkbox: false,
+            });
+        }
+        for row in self.rows() {
+            path.push(&row.name);
+            row.form.visit_errors(path, f);
+            path.pop();
+        }
+    }
+}
+
+/// The values of `items` if it's a fixed set of constants (ex: from
+/// `CheckboxGroup`), with titles.
+fn const_choices(items: &Value) -> Option<Vec<(Value, String)>> {
+    if let Some(e) = items.get("enum").and_then(|e| e.as_array()) {
+        return Some(e.iter().map(|v| (v.clone(), v.as_str().map(|v| v.to_string()).unwrap_or_else(|| v.to_string()))).collect());
+    }
+    let alternatives = items.get("oneOf").or_else(|| items.get("anyOf")).and_then(|a| a.as_array())?;
+    return alternatives.iter().map(|a| {
+        let c = a.get("const")?;
+        let title = a.get("title").and_then(|t| t.as_str()).map(|t| t.to_string()).unwrap_or_else(|| match c {
+            Value::String(c) => c.clone(),
+            c => c.to_string(),
+        });
+        return Some((c.clone(), title));
+    }).collect();
+}
+
+fn new_array(label: &str, schema: &Value, from: Option<&Value>) -> Result<Box<dyn FormState<Value>>, String> {
+    let from_items = from.and_then(|v| v.as_array());
+    if let Some(prefix) = schema.get("prefixItems").and_then(|p| p.as_array()) {
+        if schema.get("items").map(|i| i != &Value::Bool(false)).unwrap_or(false) {
+            return Err(format!("Arrays with both `prefixItems` and `items` aren't supported ({})", label));
+        }
+        let mut items = vec![];
+        for (i, item) in prefix.iter().enumerate() {
+            let from = from_items.and_then(|f| f.get(i));
+            let form = DynamicForm::new(&row_label(label, i), item, from)?;
+            items.push(MapFormState::new(form, |v| Box::new(v) as Box<dyn Any>));
+        }
+        return Ok(RowFormState::new(label, items, |values| {
+            return Value::Array(values.into_iter().map(|v| *v.downcast::<Value>().unwrap()).collect());
+        }));
+    }
+    let unique = schema.get("uniqueItems").and_then(|u| u.as_bool()).unwrap_or(false);
+    if let Some(choices) = schema.get("items").filter(|_| unique).and_then(const_choices) {
+        let checked =
+            choices
+                .iter()
+                .enumerate()
+                .filter(|(_, (v, _))| from_items.map(|f| f.contains(v)).unwrap_or(false))
+                .map(|(i, _)| i)
+                .collect::<Vec<_>>();
+        let names =
+            choices
+                .iter()
+                .map(|(v, title)| (v.as_str().map(|v| v.to_string()).unwrap_or_else(|| v.to_string()), title.clone()))
+                .collect();
+        let values = choices.into_iter().map(|(v, _)| v).collect::<Vec<_>>();
+        let min = schema.get("minItems").and_then(|v| v.as_u64()).map(|v| v as usize).unwrap_or(0);
+        let max = schema.get("maxItems").and_then(|v| v.as_u64()).map(|v| v as usize).unwrap_or(usize::MAX);
+        return Ok(
+            MapFormState::new(
+                Box::new(CheckboxGroupFormState::build(label, names, &checked, min, max, Box::new(move || values.clone()))),
+                Value::Array,
+            ),
+        );
+    }
+    return Ok(Box::new(DynamicList::new(label, schema, from)?));
+}
+
+impl DynamicForm {
+    /// Build a form for any schema (not just objects).
+    ///
+    /// * `label` - the title of the form, for `aria-label`
+    ///
+    /// * `from` - an existing value to initially show
+    ///
+    /// Returns an error if part of the schema isn't supported.
+    pub fn new(label: &str, schema: &Value, from: Option<&Value>) -> Result<Box<dyn FormState<Value>>, String> {
+        if schema.get("$ref").is_some() {
+            return Err(format!("$ref isn't supported ({})", label));
+        }
+        if let Some(c) = schema.get("const") {
+            let c = c.clone();
+            return Ok(UnitFormState::new(move || c.clone()));
+        }
+        if let Some(e) = schema.get("enum").and_then(|e| e.as_array()) {
+            let schemas = e.iter().map(|v| {
+                let title = v.as_str().map(|v| v.to_string()).unwrap_or_else(|| v.to_string());
+                return serde_json::json!({
+                    "title": title,
+                    "const": v
+                });
+            }).collect::<Vec<_>>();
+            return new_variants(label, &schemas, from);
+        }
+        if let Some(alternatives) =
+            schema.get("oneOf").or_else(|| schema.get("anyOf")).and_then(|a| a.as_array()) {
+            let non_null = alternatives.iter().filter(|s| !is_null_schema(s)).cloned().collect::<Vec<_>>();
+            if non_null.len() < alternatives.len() {
+                if non_null.len() == 1 {
+                    return new_nullable(label, &non_null[0], from);
+                }
+                let mut schema = schema.clone();
+                let schema_obj = schema.as_object_mut().unwrap();
+                schema_obj.remove("anyOf");
+                schema_obj.insert("oneOf".to_string(), Value::Array(non_null));
+                return new_nullable(label, &schema, from);
+            }
+            return new_variants(label, alternatives, from);
+        }
+        if let Some(types) = schema.get("type").and_then(|t| t.as_array()) {
+            let non_null = types.iter().filter(|t| t.as_str() != Some("null")).collect::<Vec<_>>();
+            if non_null.len() != 1 {
+                return Err(format!("Schemas with multiple types aren't supported ({})", label));
+            }
+            let mut schema = schema.clone();
+            schema["type"] = non_null[0].clone();
+            if types.len() == 1 {
+                return DynamicForm::new(label, &schema, from);
+            }
+            return new_nullable(label, &schema, from);
+        }
+        match schema_type(schema) {
+            Some("string") => {
+                let type_ = if schema.get("format").and_then(|f| f.as_str()) == Some("password") {
+                    "password"
+                } else {
+                    "text"
+                };
+                let initial = from.and_then(|v| v.as_str()).unwrap_or_default().to_string();
+                return Ok(MapFormState::new(FromStrFormState::new::<_, String>(label, type_, initial), Value::String));
+            },
+            Some("integer") => {
+                let initial = from.filter(|v| v.is_number()).map(|v| v.to_string()).unwrap_or_default();
+                return Ok(MapFormState::new(FromStrFormState::new::<_, i64>(label, "text", initial), Value::from));
+            },
+            Some("number") => {
+                let initial = from.filter(|v| v.is_number()).map(|v| v.to_string()).unwrap_or_default();
+                return Ok(MapFormState::new(FromStrFormState::new::<_, f64>(label, "text", initial), Value::from));
+            },
+            Some("boolean") => {
+                return Ok(
+                    MapFormState::new(
+                        BoolFormState::new(label, from.and_then(|v| v.as_bool()).unwrap_or_default()),
+                        Value::Bool,
+                    ),
+                );
+            },
+            Some("null") => {
+                return Ok(UnitFormState::new(|| Value::Null));
+            },
+            Some("object") => {
+                return Ok(Box::new(DynamicForm::new_object(schema, from)?));
+            },
+            Some("array") => return new_array(label, schema, from),
+            Some(t) => return Err(format!("Unknown schema type [{}] ({})", t, label)),
+            None => return Err(format!("Schema has no type ({})", label)),
+        }
+    }
+
+    fn parse_fields(&self, parse: impl Fn(&dyn FormState<Value>) -> Result<Value, ()>) -> Result<Value, ()> {
+        let mut out = Map::new();
+        let mut errors = false;
+        for f in &self.fields {
+            match parse(f.form.as_ref()) {
+                Ok(Value::Null) if f.optional => { },
+                Ok(v) => {
+                    out.insert(f.name.clone(), v);
+                },
+                Err(()) => {
+                    errors = true;
+                },
+            }
+        }
+        if errors {
+            return Err(());
+        }
+        return Ok(Value::Object(out));
+    }
+
+    fn new_object(schema: &Value, from: Option<&Value>) -> Result<DynamicForm, String> {
+        let empty = Map::new();
+        let properties = schema.get("properties").and_then(|p| p.as_object()).unwrap_or(&empty);
+        let required =
+            schema
+                .get("required")
+                .and_then(|r| r.as_array())
+                .map(|r| r.iter().filter_map(|k| k.as_str()).collect::<Vec<_>>())
+                .unwrap_or_default();
+        let mut fields = vec![];
+        let mut elements = vec![];
+        for (name, f_schema) in properties {
+            let title = f_schema.get("title").and_then(|t| t.as_str()).unwrap_or(name);
+            let f_from = from.and_then(|v| v.get(name));
+            let optional = !required.contains(&name.as_str());
+            let form = if optional && !is_nullable(f_schema) {
+                new_nullable(title, f_schema, f_from)?
+            } else {
+                DynamicForm::new(title, f_schema, f_from)?
+            };
+            let subelements = form.elements();
+            if schema_type(f_schema) == Some("object") {
+                elements.push(
+                    el("fieldset")
+                        .classes(&[CSS_CLASS_SUBFORM])
+                        .push(el("legend").text(title))
+                        .extend(subelements.error.into_iter().collect())
+                        .extend(subelements.elements),
+                );
+            } else {
+                elements.extend(subelements.error.into_iter());
+                elements.push(el("span").classes(&[CSS_CLASS_LABEL]).text(title));
+                elements.extend(subelements.elements);
+            }
+            fields.push(DynamicField {
+                name: name.clone(),
+                optional: optional,
+                form: form,
+            });
+        }
+        return Ok(DynamicForm {
+            fields: fields,
+            elements: elements,
+        });
+    }
+}
+
+impl FormState<Value> for DynamicForm {
+    fn elements(&self) -> FormElements {
+        return FormElements {
+            error: None,
+            elements: self.elements.clone(),
+        };
+    }
+
+    fn parse(&self) -> Result<Value, ()> {
+        return self.parse_fields(|f| f.parse());
+    }
+
+    fn peek(&self) -> Result<Value, ()> {
+        return self.parse_fields(|f| f.peek());
+    }
+
+    fn on_change(&self, cb: Rc<dyn Fn()>) {
+        for f in &self.fields {
+            f.form.on_change(cb.clone());
+        }
+    }
+
+    fn is_dirty(&self) -> bool {
+        return self.fields.iter().any(|f| f.form.is_dirty());
+    }
+
+    fn reset(&self) {
+        for f in &self.fields {
+            f.form.reset();
+        }
+    }
+
+    fn set_disabled(&self, disabled: bool) {
+        for f in &self.fields {
+            f.form.set_disabled(disabled);
+        }
+    }
+
+    fn get_raw(&self) -> RawState {
+        return RawState::List(self.fields.iter().map(|f| f.form.get_raw()).collect());
+    }
+
+    fn set_raw(&self, raw: &RawState) {
+        let RawState::List(raw) = raw else {
+            return;
+        };
+        if raw.len() != self.fields.len() {
+            return;
+        }
+        for (f, raw) in self.fields.iter().zip(raw) {
+            f.form.set_raw(raw);
+        }
+    }
+
+    fn field(&self, path: &[&str]) -> Option<FieldHandle> {
+        let (first, path) = path.split_first()?;
+        return self.fields.iter().find(|f| f.name == *first)?.form.field(path);
+    }
+
+    fn visit_fields<'a>(&'a self, path: &mut Vec<&'a str>, f: &mut dyn FnMut(&[&'a str], FieldHandle)) {
+        for field in &self.fields {
+            path.push(&field.name);
+            field.form.visit_fields(path, f);
+            path.pop();
+        }
+    }
+
+    fn visit_errors<'a>(&'a self, path: &mut Vec<&'a str>, f: &mut dyn FnMut(&[&'a str], FieldHandle)) {
+        for field in &self.fields {
+            path.push(&field.name);
+            field.form.visit_errors(path, f);
+            path.pop();
+        }
+    }
+}
@@ -1,20 +1,44 @@
 use std::{
+    any::Any,
+    borrow::Cow,
+    cell::{
+        Cell,
+        OnceCell,
+        RefCell,
+    },
+    collections::{
+        BTreeSet,
+        HashSet,
+    },
     fmt::Display,
+    hash::Hash,
+    num::Wrapping,
     str::FromStr,
+    sync::Arc,
     convert::Infallible,
+    rc::Rc,
 };
-use rooting::{
+pub use rooting_forms_proc_macros::Form;
+pub use serde_json;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use serde_json::json;
+use dom::{
     El,
     el,
 };
-pub use rooting_forms_proc_macros::Form;
-use wasm_bindgen::JsCast;
-use web_sys::HtmlInputElement;
 
-/// Republished types for macro use.
-pub mod republish {
-    pub use web_sys::HtmlSelectElement;
-}
+pub mod dom;
+pub mod submit;
+pub mod wizard;
+pub mod draft;
+pub mod dynamic;
+pub mod summary;
+pub mod testing;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod server;
 
 /// Used for the text label before form fields.
 pub const CSS_CLASS_LABEL: &'static str = "form_label";
@@ -34,13 +58,50 @@ pub const CSS_CLASS_OPTION_ENABLE: &'static str = "form_input_option";
 /// associated label, if there is one).
 pub const CSS_CLASS_ERROR: &'static str = "form_error";
 
-/// Used for nested struct/enum fields, namely within variants or options.
+/// Used for nested struct/enum fields, namely within variants or options. Nested
+/// derived structs use this on a `<fieldset>`.
 pub const CSS_CLASS_SUBFORM: &'static str = "subform";
 
 /// Used to hide disabled variants - hidden to keep user input in case they
 /// re-enable later.
 pub const CSS_CLASS_HIDDEN: &'static str = "disable_hide";
 
+/// Used for values in read-only displays (`Form::display`), along with the
+/// `form_input_` class the input would have.
+pub const CSS_CLASS_DISPLAY: &'static str = "form_display";
+
+/// Used on the `<form>` element of `SubmitForm` and `Wizard`.
+pub const CSS_CLASS_FORM: &'static str = "form";
+
+/// Used on the submit button of `SubmitForm`, and the next/finish button of
+/// `Wizard`.
+pub const CSS_CLASS_SUBMIT: &'static str = "form_submit";
+
+/// Used on the back button of `Wizard`.
+pub const CSS_CLASS_BACK: &'static str = "form_back";
+
+/// Used on the element containing the inputs of a tuple or array, which should
+/// be laid out in a single row.
+pub const CSS_CLASS_ROW: &'static str = "form_row";
+
+/// Used on the element containing the rows of a variable-length array in a
+/// `DynamicForm`, followed by the add button.
+pub const CSS_CLASS_LIST: &'static str = "form_list";
+
+/// Used on the button that adds a row to a `DynamicForm` array.
+pub const CSS_CLASS_LIST_ADD: &'static str = "form_list_add";
+
+/// Used on the button that removes a row from a `DynamicForm` array, after the
+/// row's inputs.
+pub const CSS_CLASS_LIST_REMOVE: &'static str = "form_list_remove";
+
+/// Used on the element containing the checkboxes of a `CheckboxGroup`, each
+/// followed by a label.
+pub const CSS_CLASS_CHOICES: &'static str = "form_choices";
+
+/// Used on the `ErrorSummary` list.
+pub const CSS_CLASS_ERROR_SUMMARY: &'static str = "form_error_summary";
+
 /// This should be used on all inputs, since `<label>` isn't used.
 pub const ATTR_LABEL: &'static str = "aria-label";
 
@@ -59,18 +120,254 @@ pub trait FormState<T> {
 
     /// Parse the elements into the resulting type.
     fn parse(&self) -> Result<T, ()>;
+
+    /// Parse like `parse`, but without displaying or recording validation errors,
+    /// ex: to decide what to show while the user is still typing. Errors shown by
+    /// an earlier `parse` are left as they are.
+    fn peek(&self) -> Result<T, ()>;
+
+    /// Register a callback that's called after any input in this form or subform
+    /// changes, including `Option` toggles and enum variant selection. To get the
+    /// current value, call `parse` in the callback.
+    fn on_change(&self, cb: Rc<dyn Fn()>);
+
+    /// Returns true if the inputs differ from the value the form was created with.
+    fn is_dirty(&self) -> bool;
+
+    /// Restore the inputs to the value the form was created with, clearing any
+    /// validation errors.
+    fn reset(&self);
+
+    /// Disable or re-enable every input in the form, ex: while saving. Inputs that
+    /// were already disabled before the form was disabled stay disabled.
+    fn set_disabled(&self, disabled: bool);
+
+    /// Get the unparsed state of the inputs, including invalid text, hidden enum
+    /// variants, and disabled options.
+    fn get_raw(&self) -> RawState;
+
+    /// Restore the inputs from a `get_raw` result. Parts of `raw` that don't match
+    /// the form's structure are ignored.
+    fn set_raw(&self, raw: &RawState);
+
+    /// Find a field by path. The path segments are struct field names, enum variant
+    /// names (ex: `Some` for options), or empty to refer to this form. For an
+    /// `Option` or enum with an empty path this returns the enable checkbox or
+    /// variant `<select>`. Returns `None` if the path doesn't exist or refers to a
+    /// struct.
+    fn field(&self, path: &[&str]) -> Option<FieldHandle>;
+
+    /// Call `f` with the path (see `field`) and handle of every input in the form,
+    /// including inputs that are currently hidden. `path` is the path of this form.
+    /// Enum variants that haven't been selected yet have no inputs (they're built on
+    /// first selection), so they're skipped.
+    fn visit_fields<'a>(&'a self, path: &mut Vec<&'a str>, f: &mut dyn FnMut(&[&'a str], FieldHandle));
+
+    /// Call `f` with the path and handle of each input that failed validation in the
+    /// last `parse`, in document order. Inputs that weren't parsed (unselected
+    /// variants, disabled options, hidden conditional fields) are skipped.
+    fn visit_errors<'a>(&'a self, path: &mut Vec<&'a str>, f: &mut dyn FnMut(&[&'a str], FieldHandle));
+
+    /// Focus the first input that failed validation in the last `parse` and scroll
+    /// it into view. Returns false if there were no invalid inputs.
+    fn focus_first_invalid(&self) -> bool {
+        let mut first = None;
+        self.visit_errors(&mut vec![], &mut |_, f| {
+            if first.is_none() {
+                first = Some(f);
+            }
+        });
+        let Some(first) = first else {
+            return false;
+        };
+        dom::scroll_into_view(&first.input);
+        dom::focus(&first.input);
+        return true;
+    }
+}
+
+/// A single input in a form, see `FormState::field`. Use this to focus an input,
+/// prefill it, or add listeners.
+pub struct FieldHandle {
+    /// The text input, checkbox, or `<select>`.
+    pub input: El,
+    /// The element validation errors are displayed in, if the input has one.
+    pub error: Option<El>,
+    /// If true, `input` is a checkbox and its value is `true` or `false`.
+    pub checkbox: bool,
+}
+
+impl FieldHandle {
+    /// The raw value of the input: the entered text, `true`/`false` for checkboxes,
+    /// the selected variant index for enums, or the selected option text for
+    /// `SelectFormState`.
+    pub fn get_value(&self) -> String {
+        if self.checkbox {
+            return dom::checked(&self.input).to_string();
+        }
+        return dom::value(&self.input);
+    }
+
+    /// Replace the raw value of the input (see `get_value`), firing the events a
+    /// user's edit would so dependent elements update.
+    pub fn set_value(&self, value: &str) {
+        if self.checkbox {
+            dom::set_checked(&self.input, value == "true");
+            dom::dispatch(&self.input, "click");
+        } else {
+            dom::set_value(&self.input, value);
+            dom::dispatch(&self.input, "input");
+        }
+        dom::dispatch(&self.input, "change");
+    }
+}
+
+/// The unparsed state of a form's inputs, ex: for saving drafts.
+///
+/// Text inputs are `Text` and checkboxes are `Bool`. Structs are a `List` of their
+/// fields, options are a `List` of the enabled checkbox and the value, and enums
+/// are a `List` of the selected variant index (as `Text`) followed by every
+/// variant.
+///
+/// This serializes as plain JSON strings, booleans and arrays.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RawState {
+    Text(String),
+    Bool(bool),
+    List(Vec<RawState>),
 }
 
 /// This represnts a rust datatype that can be included in a form.
 pub trait Form {
-    /// Generates a form for a new value (no existing value).
+    /// When nested in a derived struct, wrap the elements in a `<fieldset>` with the
+    /// field title as the `<legend>` rather than placing a label before them. This
+    /// is set for derived structs.
+    const FIELDSET: bool = false;
+
+    /// When nested in a derived struct, the field isn't listed as `required` in the
+    /// JSON Schema. This is set for `Option`.
+    const OPTIONAL: bool = false;
+
+    /// Generates a form, with the inputs initially set from `from` if present.
     ///
     /// * `field` - is the field name, for accessibility using `aria-label`. `<label>`
     ///   isn't used sometime due to anonymous fields in tuples.
-    fn new_form(field: &'static str) -> Box<dyn FormState<Self>>;
+    fn new_form_with(field: &str, from: Option<&Self>) -> Box<dyn FormState<Self>>;
+
+    /// Generates a form for a new value (no existing value).
+    fn new_form(field: &str) -> Box<dyn FormState<Self>> {
+        return Self::new_form_with(field, None);
+    }
+
+    /// Generates read-only elements showing `value`, with the same layout and
+    /// titles as the form.
+    ///
+    /// * `field` - is the field name, for accessibility using `aria-label`.
+    fn display(field: &str, value: &Self) -> Vec<El>;
+
+    /// Describes the values of this type as a JSON Schema, ex: for documentation or
+    /// non-Rust tools. Titles come from `#[title]` and descriptions from doc
+    /// comments, the same as the generated forms.
+    ///
+    /// * `title` - is the field name, used for the schema `title`.
+    fn schema(title: &str) -> serde_json::Value {
+        return json!({
+            "title": title
+        });
+    }
+}
+
+/// A widget for fields marked `#[form(with = Widget)]`, used instead of the field
+/// type's `Form` implementation. This lets a field keep its type while changing
+/// the input, ex: a `String` field with `#[form(with = rooting_forms::Password)]`.
+/// The methods are the same as `Form`.
+pub trait FormWith<T> {
+    const FIELDSET: bool = false;
+    const OPTIONAL: bool = false;
+
+    fn new_form_with(field: &str, from: Option<&T>) -> Box<dyn FormState<T>>;
+
+    fn display(field: &str, value: &T) -> Vec<El>;
+
+    fn schema(title: &str) -> serde_json::Value {
+        return json!({
+            "title": title
+        });
+    }
+}
+
+/// One step of a form split with `#[form(step = "...")]`.
+pub struct FormStep {
+    /// The step title, from the `step` attribute.
+    pub title: &'static str,
+    /// The elements of the fields in this step.
+    pub elements: Vec<El>,
+    /// Parses just the fields in this step, displaying any validation errors.
+    /// Returns true if there were no issues.
+    pub validate: Box<dyn Fn() -> bool>,
+}
+
+/// A derived struct with fields grouped into steps using `#[form(step = "Step
+/// title")]`, for use with `Wizard`. A field without a `step` is part of the same
+/// step as the previous field.
+pub trait FormSteps: Form {
+    /// Generates a form like `new_form_with`, plus the fields grouped by step.
+    fn new_steps_form(from: Option<&Self>) -> (Box<dyn FormState<Self>>, Vec<FormStep>);
+}
+
+impl<T, S: FormState<T> + ?Sized> FormState<T> for Rc<S> {
+    fn elements(&self) -> FormElements {
+        return self.as_ref().elements();
+    }
+
+    fn parse(&self) -> Result<T, ()> {
+        return self.as_ref().parse();
+    }
+
+    fn peek(&self) -> Result<T, ()> {
+        return self.as_ref().peek();
+    }
+
+    fn on_change(&self, cb: Rc<dyn Fn()>) {
+        self.as_ref().on_change(cb);
+    }
+
+    fn is_dirty(&self) -> bool {
+        return self.as_ref().is_dirty();
+    }
+
+    fn reset(&self) {
+        self.as_ref().reset();
+    }
+
+    fn set_disabled(&self, disabled: bool) {
+        self.as_ref().set_disabled(disabled);
+    }
+
+    fn get_raw(&self) -> RawState {
+        return self.as_ref().get_raw();
+    }
+
+    fn set_raw(&self, raw: &RawState) {
+        self.as_ref().set_raw(raw);
+    }
+
+    fn field(&self, path: &[&str]) -> Option<FieldHandle> {
+        return self.as_ref().field(path);
+    }
+
+    fn visit_fields<'a>(&'a self, path: &mut Vec<&'a str>, f: &mut dyn FnMut(&[&'a str], FieldHandle)) {
+        self.as_ref().visit_fields(path, f);
+    }
+
+    fn visit_errors<'a>(&'a self, path: &mut Vec<&'a str>, f: &mut dyn FnMut(&[&'a str], FieldHandle)) {
+        self.as_ref().visit_errors(path, f);
+    }
 }
 
 /// A minimal string wrapper that creates a password form input.
+#[derive(Default)]
 pub struct Password(String);
 
 impl FromStr for Password {
@@ -82,6 +379,7 @@ impl FromStr for Password {
 }
 
 /// A minimal string wrapper that creates a textarea form input.
+#[derive(Default)]
 pub struct BigString(String);
 
 impl FromStr for BigString {
@@ -92,37 +390,126 @@ impl FromStr for BigString {
     }
 }
 
+/// Disables an input for `FormState::set_disabled`, remembering whether it was
+/// already disabled so that re-enabling doesn't override that.
+#[derive(Default)]
+struct Disabler(Cell<Option<bool>>);
+
+impl Disabler {
+    fn set(&self, input: &El, disabled: bool) {
+        match (disabled, self.0.get()) {
+            (true, None) => {
+                self.0.set(Some(dom::disabled(input)));
+                input.ref_attr("disabled", "disabled");
+            },
+            (false, Some(was_disabled)) => {
+                self.0.set(None);
+                if !was_disabled {
+                    input.ref_remove_attr("disabled");
+                }
+            },
+            _ => { },
+        }
+    }
+}
+
+/// The read-only element for text values, used by `Form::display`.
+pub fn display_text(label: &str, text: &str) -> El {
+    return el("span").classes(&[CSS_CLASS_SMALL_INPUT, CSS_CLASS_DISPLAY]).attr(ATTR_LABEL, label).text(text);
+}
+
+/// A masked value, since passwords shouldn't be shown.
+fn display_password(label: &str) -> El {
+    return display_text(label, &"•".repeat(8));
+}
+
+/// Multi-line text, one `<span>` per line.
+fn display_big_text(label: &str, text: &str) -> El {
+    let out = el("span").classes(&[CSS_CLASS_BIG_INPUT, CSS_CLASS_DISPLAY]).attr(ATTR_LABEL, label);
+    for (i, line) in text.lines().enumerate() {
+        if i > 0 {
+            out.ref_push(el("br"));
+        }
+        out.ref_push(el("span").text(line));
+    }
+    return out;
+}
+
+/// A disabled checkbox, used by `Form::display` for `bool` and `Option`.
+fn display_checkbox(label: &str, class: &str, checked: bool) -> El {
+    let input =
+        el("input")
+            .classes(&[class, CSS_CLASS_DISPLAY])
+            .attr(ATTR_LABEL, label)
+            .attr("type", "checkbox")
+            .attr("disabled", "disabled");
+    dom::set_checked(&input, checked);
+    return input;
+}
+
 /// A helper form type for rust types that implement `FromStr`.
 pub struct FromStrFormState {
     el: El,
     error_el: El,
+    initial: String,
+    disabled: Disabler,
+    /// The last `parse` failed.
+    invalid: Cell<bool>,
 }
 
 impl FromStrFormState {
-    pub fn new<E: Display, T: FromStr<Err = E>>(label: &str, type_: &str) -> Box<dyn FormState<T>> {
-        let error_el = el("span").classes(&[CSS_CLASS_ERROR]);
-        return Box::new(FromStrFormState {
-            el: el("input")
+    /// * `initial` - the starting text of the input, also used for `is_dirty` and
+    ///   `reset`.
+    pub fn new<
+        E: Display,
+        T: FromStr<Err = E>,
+    >(label: &str, type_: &str, initial: String) -> Box<dyn FormState<T>> {
+        let input =
+            el("input")
                 .classes(&[CSS_CLASS_SMALL_INPUT])
                 .attr(ATTR_LABEL, label)
                 .attr("type", type_)
-                .on("change", {
-                    let error_el = error_el.clone();
-                    move |ev| {
-                        let text = ev.target().unwrap().dyn_ref::<HtmlInputElement>().unwrap().value();
-                        if text.len() >= 1 {
-                            match T::from_str(&text) {
-                                Err(e) => {
-                                    error_el.ref_text(&e.to_string());
-                                    return;
-                                },
-                                _ => { },
-                            }
-                        }
-                        error_el.ref_text("");
+                .attr("value", &initial);
+        return Self::build(input, initial);
+    }
+
+    /// Like `new` but with a multi-line `<textarea>`, with `CSS_CLASS_BIG_INPUT`.
+    pub fn new_big<
+        E: Display,
+        T: FromStr<Err = E>,
+    >(label: &str, initial: String) -> Box<dyn FormState<T>> {
+        let input = el("textarea").classes(&[CSS_CLASS_BIG_INPUT]).attr(ATTR_LABEL, label).text(&initial);
+        return Self::build(input, initial);
+    }
+
+    fn build<E: Display, T: FromStr<Err = E>>(input: El, initial: String) -> Box<dyn FormState<T>> {
+        let error_el = el("span").classes(&[CSS_CLASS_ERROR]);
+        input.ref_on("change", {
+            let input = input.weak();
+            let error_el = error_el.clone();
+            move |_| {
+                let Some(input) = input.upgrade() else {
+                    return;
+                };
+                let text = dom::value(&input);
+                if text.len() >= 1 {
+                    match T::from_str(&text) {
+                        Err(e) => {
+                            error_el.ref_text(&e.to_string());
+                            return;
+                        },
+                        _ => { },
                     }
-                }),
+                }
+                error_el.ref_text("");
+            }
+        });
+        return Box::new(FromStrFormState {
+            el: input,
             error_el: error_el,
+            initial: initial,
+            disabled: Default::default(),
+            invalid: Cell::new(false),
         });
     }
 }
@@ -136,180 +523,1842 @@ impl<E: Display, T: FromStr<Err = E>> FormState<T> for FromStrFormState {
     }
 
     fn parse(&self) -> Result<T, ()> {
-        match T::from_str(&self.el.raw().dyn_ref::<HtmlInputElement>().unwrap().value()) {
+        match T::from_str(&dom::value(&self.el)) {
             Ok(v) => {
                 self.error_el.ref_text("");
+                self.invalid.set(false);
                 return Ok(v);
             },
             Err(e) => {
                 self.error_el.ref_text(&e.to_string());
+                self.invalid.set(true);
                 return Err(());
             },
         }
     }
-}
 
-impl Form for String {
-    fn new_form(field: &'static str) -> Box<dyn FormState<Self>> {
-        return FromStrFormState::new::<_, String>(field, "text");
+    fn peek(&self) -> Result<T, ()> {
+        return T::from_str(&dom::value(&self.el)).map_err(|_| ());
     }
-}
 
-impl Form for Password {
-    fn new_form(field: &'static str) -> Box<dyn FormState<Self>> {
-        return FromStrFormState::new::<_, Password>(field, "password");
+    fn on_change(&self, cb: Rc<dyn Fn()>) {
+        self.el.ref_on("input", move |_| cb());
     }
-}
 
-impl Form for BigString {
-    fn new_form(field: &'static str) -> Box<dyn FormState<Self>> {
-        return FromStrFormState::new::<_, BigString>(field, "text");
+    fn is_dirty(&self) -> bool {
+        return dom::value(&self.el) != self.initial;
     }
-}
 
-impl Form for u8 {
-    fn new_form(field: &'static str) -> Box<dyn FormState<Self>> {
-        return FromStrFormState::new::<_, Self>(field, "text");
+    fn reset(&self) {
+        dom::set_value(&self.el, &self.initial);
+        self.error_el.ref_text("");
+        self.invalid.set(false);
     }
-}
 
-impl Form for u16 {
-    fn new_form(field: &'static str) -> Box<dyn FormState<Self>> {
-        return FromStrFormState::new::<_, Self>(field, "text");
+    fn set_disabled(&self, disabled: bool) {
+        self.disabled.set(&self.el, disabled);
     }
-}
 
-impl Form for u32 {
-    fn new_form(field: &'static str) -> Box<dyn FormState<Self>> {
-        return FromStrFormState::new::<_, Self>(field, "text");
+    fn get_raw(&self) -> RawState {
+        return RawState::Text(dom::value(&self.el));
     }
-}
 
-impl Form for u64 {
-    fn new_form(field: &'static str) -> Box<dyn FormState<Self>> {
-        return FromStrFormState::new::<_, Self>(field, "text");
+    fn set_raw(&self, raw: &RawState) {
+        let RawState::Text(text) = raw else {
+            return;
+        };
+        dom::set_value(&self.el, text);
+        self.error_el.ref_text("");
+        self.invalid.set(false);
     }
-}
 
-impl Form for i8 {
-    fn new_form(field: &'static str) -> Box<dyn FormState<Self>> {
-        return FromStrFormState::new::<_, Self>(field, "text");
+    fn field(&self, path: &[&str]) -> Option<FieldHandle> {
+        if !path.is_empty() {
+            return None;
+        }
+        return Some(FieldHandle {
+            input: self.el.clone(),
+            error: Some(self.error_el.clone()),
+            checkbox: false,
+        });
     }
-}
 
-impl Form for i16 {
-    fn new_form(field: &'static str) -> Box<dyn FormState<Self>> {
-        return FromStrFormState::new::<_, Self>(field, "text");
+    fn visit_fields<'a>(&'a self, path: &mut Vec<&'a str>, f: &mut dyn FnMut(&[&'a str], FieldHandle)) {
+        f(path, FieldHandle {
+            input: self.el.clone(),
+            error: Some(self.error_el.clone()),
+            checkbox: false,
+        });
     }
-}
 
-impl Form for i32 {
-    fn new_form(field: &'static str) -> Box<dyn FormState<Self>> {
-        return FromStrFormState::new::<_, Self>(field, "text");
+    fn visit_errors<'a>(&'a self, path: &mut Vec<&'a str>, f: &mut dyn FnMut(&[&'a str], FieldHandle)) {
+        if self.invalid.get() {
+            f(path, FieldHandle {
+                input: self.el.clone(),
+                error: Some(self.error_el.clone()),
+                checkbox: false,
+            });
+        }
     }
 }
 
-impl Form for i64 {
-    fn new_form(field: &'static str) -> Box<dyn FormState<Self>> {
-        return FromStrFormState::new::<_, Self>(field, "text");
-    }
+/// A helper form type for a `<select>` of fixed values, for types that implement
+/// `FromStr` and `Display` (ex: enums from other crates that can't derive
+/// `Form`). Each option is labeled and valued by the `Display` text, which is
+/// parsed back with `FromStr`.
+pub struct SelectFormState {
+    el: El,
+    error_el: El,
+    initial: String,
+    disabled: Disabler,
+    /// The last `parse` failed.
+    invalid: Cell<bool>,
 }
 
-impl Form for f32 {
-    fn new_form(field: &'static str) -> Box<dyn FormState<Self>> {
-        return FromStrFormState::new::<_, Self>(field, "text");
+impl SelectFormState {
+    /// * `values` - the choices, in order
+    ///
+    /// * `initial` - the value initially selected, otherwise the first value. Also
+    ///   used for `is_dirty` and `reset`.
+    pub fn new<
+        E: Display,
+        T: FromStr<Err = E> + Display,
+    >(label: &str, values: impl IntoIterator<Item = T>, initial: Option<&T>) -> Box<dyn FormState<T>> {
+        let select = el("select").classes(&[CSS_CLASS_SMALL_INPUT]).attr(ATTR_LABEL, label);
+        let initial = initial.map(|v| v.to_string());
+        let mut first = None;
+        let mut matched = None;
+        for v in values {
+            let text = v.to_string();
+            let option = el("option").text(&text).attr("value", &text);
+            if initial.as_ref() == Some(&text) {
+                option.ref_attr("selected", "selected");
+                matched = Some(text.clone());
+            }
+            select.ref_push(option);
+            first.get_or_insert(text);
+        }
+        return Box::new(SelectFormState {
+            el: select,
+            error_el: el("span").classes(&[CSS_CLASS_ERROR]),
+            // An initial value that isn't one of the options can't be shown, so the
+            // select starts on the first option
+            initial: matched.or(first).unwrap_or_default(),
+            disabled: Default::default(),
+            invalid: Cell::new(false),
+        });
     }
 }
 
-impl Form for f64 {
-    fn new_form(field: &'static str) -> Box<dyn FormState<Self>> {
-        return FromStrFormState::new::<_, Self>(field, "text");
+impl<E: Display, T: FromStr<Err = E>> FormState<T> for SelectFormState {
+    fn elements(&self) -> FormElements {
+        return FormElements {
+            error: Some(self.error_el.clone()),
+            elements: vec![self.el.clone()],
+        };
     }
-}
 
-struct BoolFormState {
-    input: El,
-}
+    fn parse(&self) -> Result<T, ()> {
+        match T::from_str(&dom::value(&self.el)) {
+            Ok(v) => {
+                self.error_el.ref_text("");
+                self.invalid.set(false);
+                return Ok(v);
+            },
+            Err(e) => {
+                self.error_el.ref_text(&e.to_string());
+                self.invalid.set(true);
+                return Err(());
+            },
+        }
+    }
 
-impl FormState<bool> for BoolFormState {
-    fn elements(&self) -> FormElements {
-        return FormElements {
-            error: None,
-            elements: vec![self.input.clone()],
+    fn peek(&self) -> Result<T, ()> {
+        return T::from_str(&dom::value(&self.el)).map_err(|_| ());
+    }
+
+    fn on_change(&self, cb: Rc<dyn Fn()>) {
+        self.el.ref_on("change", move |_| cb());
+    }
+
+    fn is_dirty(&self) -> bool {
+        return dom::value(&self.el) != self.initial;
+    }
+
+    fn reset(&self) {
+        dom::set_value(&self.el, &self.initial);
+        self.error_el.ref_text("");
+        self.invalid.set(false);
+    }
+
+    fn set_disabled(&self, disabled: bool) {
+        self.disabled.set(&self.el, disabled);
+    }
+
+    fn get_raw(&self) -> RawState {
+        return RawState::Text(dom::value(&self.el));
+    }
+
+    fn set_raw(&self, raw: &RawState) {
+        let RawState::Text(text) = raw else {
+            return;
         };
+        dom::set_value(&self.el, text);
+        self.error_el.ref_text("");
+        self.invalid.set(false);
     }
 
-    fn parse(&self) -> Result<bool, ()> {
-        return Ok(self.input.raw().dyn_ref::<HtmlInputElement>().unwrap().checked());
+    fn field(&self, path: &[&str]) -> Option<FieldHandle> {
+        if !path.is_empty() {
+            return None;
+        }
+        return Some(FieldHandle {
+            input: self.el.clone(),
+            error: Some(self.error_el.clone()),
+            checkbox: false,
+        });
+    }
+
+    fn visit_fields<'a>(&'a self, path: &mut Vec<&'a str>, f: &mut dyn FnMut(&[&'a str], FieldHandle)) {
+        f(path, FieldHandle {
+            input: self.el.clone(),
+            error: Some(self.error_el.clone()),
+            checkbox: false,
+        });
+    }
+
+    fn visit_errors<'a>(&'a self, path: &mut Vec<&'a str>, f: &mut dyn FnMut(&[&'a str], FieldHandle)) {
+        if self.invalid.get() {
+            f(path, FieldHandle {
+                input: self.el.clone(),
+                error: Some(self.error_el.clone()),
+                checkbox: false,
+            });
+        }
     }
 }
 
-impl Form for bool {
-    fn new_form(field: &'static str) -> Box<dyn FormState<Self>> {
-        return Box::new(
-            BoolFormState {
-                input: el("input")
-                    .classes(&[CSS_CLASS_SMALL_INPUT])
-                    .attr(ATTR_LABEL, field)
-                    .attr("type", "checkbox"),
+thread_local!{
+    /// The derived types whose schemas are being built, and whether each has been
+    /// referenced recursively.
+    static SCHEMA_STACK: RefCell<Vec<(&'static str, bool)>> = RefCell::new(vec![]);
+}
+
+/// Used by derived `Form::schema` to support recursive types. If the type named
+/// `name` is already being built further up, this returns a `$ref` to it instead of
+/// calling `build`, and the outer schema gets a matching `$anchor`.
+pub fn recursive_schema(
+    name: &'static str,
+    title: &str,
+    build: impl FnOnce() -> serde_json::Value,
+) -> serde_json::Value {
+    let recursive = SCHEMA_STACK.with(|s| {
+        let mut s = s.borrow_mut();
+        match s.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, referenced)) => {
+                *referenced = true;
+                return true;
             },
-        );
+            None => {
+                s.push((name, false));
+                return false;
+            },
+        }
+    });
+    if recursive {
+        return json!({
+            "title": title,
+            "$ref": format!("#{}", name)
+        });
+    }
+    let mut schema = build();
+    let referenced = SCHEMA_STACK.with(|s| s.borrow_mut().pop().map(|(_, r)| r).unwrap_or(false));
+    if referenced {
+        if let Some(o) = schema.as_object_mut() {
+            o.insert("$anchor".to_string(), json!(name));
+        }
     }
+    return schema;
 }
 
-struct OptionFormState<T> {
-    elements: Vec<El>,
-    subform: Box<dyn FormState<T>>,
+/// The JSON Schema for a `SelectFormState`, a string limited to the `Display` text
+/// of the values.
+pub fn select_schema<T: Display>(title: &str, values: impl IntoIterator<Item = T>) -> serde_json::Value {
+    return json!({
+        "type": "string",
+        "title": title,
+        "enum": values.into_iter().map(|v| v.to_string()).collect::<Vec<_>>()
+    });
 }
 
-impl<T: Form> FormState<Option<T>> for OptionFormState<T> {
-    fn elements(&self) -> FormElements {
-        return FormElements {
-            error: None,
-            elements: self.elements.clone(),
-        };
+/// A widget for enums deriving `strum::EnumIter`, `Display` and `FromStr` (ex:
+/// with `strum::Display` and `strum::EnumString`), shown as a `<select>`. Use with
+/// `#[form(with = rooting_forms::Select)]`.
+#[cfg(feature = "strum")]
+pub struct Select;
+
+#[cfg(feature = "strum")]
+impl<
+    E: Display,
+    T: strum::IntoEnumIterator + FromStr<Err = E> + Display + 'static,
+> FormWith<T> for Select {
+    fn new_form_with(field: &str, from: Option<&T>) -> Box<dyn FormState<T>> {
+        return SelectFormState::new(field, T::iter(), from);
     }
 
-    fn parse(&self) -> Result<Option<T>, ()> {
-        let checked = self.elements[0].raw().dyn_ref::<HtmlInputElement>().unwrap().checked();
-        if checked {
-            return Ok(Some(self.subform.parse()?));
-        } else {
-            return Ok(None);
-        }
+    fn display(field: &str, value: &T) -> Vec<El> {
+        return vec![display_text(field, &value.to_string())];
+    }
+
+    fn schema(title: &str) -> serde_json::Value {
+        return select_schema(title, T::iter());
     }
 }
 
-impl<T: Form + 'static> Form for Option<T> {
-    fn new_form(field: &'static str) -> Box<dyn FormState<Self>> {
-        let subform = T::new_form(field);
-        let subform_elements = subform.elements();
-        let mut additional = vec![];
-        additional.extend(subform_elements.error.iter().cloned());
-        additional.extend(subform_elements.elements);
-        for e in &additional {
-            e.ref_modify_classes(&[(CSS_CLASS_HIDDEN, true)]);
-        }
-        let mut elements = vec![
-            //. .
-            el("input")
-                .classes(&[CSS_CLASS_OPTION_ENABLE])
-                .attr(ATTR_LABEL, &format!("{} - Enabled", field))
-                .attr("type", "checkbox")
-                .on("click", {
-                    let additional = additional.clone();
-                    move |ev| {
-                        let checked = ev.target().unwrap().dyn_ref::<HtmlInputElement>().unwrap().checked();
-                        for e in &additional {
-                            e.ref_modify_classes(&[(CSS_CLASS_HIDDEN, checked)]);
-                        }
-                    }
-                })
-        ];
-        elements.extend(additional);
-        return Box::new(OptionFormState {
-            elements: elements,
-            subform: subform,
+impl Form for String {
+    fn new_form_with(field: &str, from: Option<&Self>) -> Box<dyn FormState<Self>> {
+        return FromStrFormState::new::<_, String>(field, "text", from.map(|v| v.to_string()).unwrap_or_default());
+    }
+
+    fn display(field: &str, value: &Self) -> Vec<El> {
+        return vec![display_text(field, value)];
+    }
+
+    fn schema(title: &str) -> serde_json::Value {
+        return json!({
+            "type": "string",
+            "title": title
+        });
+    }
+}
+
+impl Form for Password {
+    fn new_form_with(field: &str, from: Option<&Self>) -> Box<dyn FormState<Self>> {
+        return FromStrFormState::new::<_, Password>(field, "password", from.map(|v| v.0.clone()).unwrap_or_default());
+    }
+
+    fn display(field: &str, _value: &Self) -> Vec<El> {
+        return vec![display_password(field)];
+    }
+
+    fn schema(title: &str) -> serde_json::Value {
+        return json!({
+            "type": "string",
+            "format": "password",
+            "title": title
         });
     }
 }
+
+impl Form for BigString {
+    fn new_form_with(field: &str, from: Option<&Self>) -> Box<dyn FormState<Self>> {
+        return FromStrFormState::new_big::<_, BigString>(field, from.map(|v| v.0.clone()).unwrap_or_default());
+    }
+
+    fn display(field: &str, value: &Self) -> Vec<El> {
+        return vec![display_big_text(field, &value.0)];
+    }
+
+    fn schema(title: &str) -> serde_json::Value {
+        return json!({
+            "type": "string",
+            "title": title
+        });
+    }
+}
+
+impl FormWith<String> for Password {
+    fn new_form_with(field: &str, from: Option<&String>) -> Box<dyn FormState<String>> {
+        return FromStrFormState::new::<_, String>(field, "password", from.cloned().unwrap_or_default());
+    }
+
+    fn display(field: &str, _value: &String) -> Vec<El> {
+        return vec![display_password(field)];
+    }
+
+    fn schema(title: &str) -> serde_json::Value {
+        return <Password as Form>::schema(title);
+    }
+}
+
+impl FormWith<String> for BigString {
+    fn new_form_with(field: &str, from: Option<&String>) -> Box<dyn FormState<String>> {
+        return FromStrFormState::new_big::<_, String>(field, from.cloned().unwrap_or_default());
+    }
+
+    fn display(field: &str, value: &String) -> Vec<El> {
+        return vec![display_big_text(field, value)];
+    }
+
+    fn schema(title: &str) -> serde_json::Value {
+        return <BigString as Form>::schema(title);
+    }
+}
+
+impl Form for u8 {
+    fn new_form_with(field: &str, from: Option<&Self>) -> Box<dyn FormState<Self>> {
+        return FromStrFormState::new::<_, Self>(field, "text", from.map(|v| v.to_string()).unwrap_or_default());
+    }
+
+    fn display(field: &str, value: &Self) -> Vec<El> {
+        return vec![display_text(field, &value.to_string())];
+    }
+
+    fn schema(title: &str) -> serde_json::Value {
+        return json!({
+            "type": "integer",
+            "title": title,
+            "minimum": Self::MIN,
+            "maximum": Self::MAX
+        });
+    }
+}
+
+impl Form for u16 {
+    fn new_form_with(field: &str, from: Option<&Self>) -> Box<dyn FormState<Self>> {
+        return FromStrFormState::new::<_, Self>(field, "text", from.map(|v| v.to_string()).unwrap_or_default());
+    }
+
+    fn display(field: &str, value: &Self) -> Vec<El> {
+        return vec![display_text(field, &value.to_string())];
+    }
+
+    fn schema(title: &str) -> serde_json::Value {
+        return json!({
+            "type": "integer",
+            "title": title,
+            "minimum": Self::MIN,
+            "maximum": Self::MAX
+        });
+    }
+}
+
+impl Form for u32 {
+    fn new_form_with(field: &str, from: Option<&Self>) -> Box<dyn FormState<Self>> {
+        return FromStrFormState::new::<_, Self>(field, "text", from.map(|v| v.to_string()).unwrap_or_default());
+    }
+
+    fn display(field: &str, value: &Self) -> Vec<El> {
+        return vec![display_text(field, &value.to_string())];
+    }
+
+    fn schema(title: &str) -> serde_json::Value {
+        return json!({
+            "type": "integer",
+            "title": title,
+            "minimum": Self::MIN,
+            "maximum": Self::MAX
+        });
+    }
+}
+
+impl Form for u64 {
+    fn new_form_with(field: &str, from: Option<&Self>) -> Box<dyn FormState<Self>> {
+        return FromStrFormState::new::<_, Self>(field, "text", from.map(|v| v.to_string()).unwrap_or_default());
+    }
+
+    fn display(field: &str, value: &Self) -> Vec<El> {
+        return vec![display_text(field, &value.to_string())];
+    }
+
+    fn schema(title: &str) -> serde_json::Value {
+        return json!({
+            "type": "integer",
+            "title": title,
+            "minimum": Self::MIN,
+            "maximum": Self::MAX
+        });
+    }
+}
+
+impl Form for i8 {
+    fn new_form_with(field: &str, from: Option<&Self>) -> Box<dyn FormState<Self>> {
+        return FromStrFormState::new::<_, Self>(field, "text", from.map(|v| v.to_string()).unwrap_or_default());
+    }
+
+    fn display(field: &str, value: &Self) -> Vec<El> {
+        return vec![display_text(field, &value.to_string())];
+    }
+
+    fn schema(title: &str) -> serde_json::Value {
+        return json!({
+            "type": "integer",
+            "title": title,
+            "minimum": Self::MIN,
+            "maximum": Self::MAX
+        });
+    }
+}
+
+impl Form for i16 {
+    fn new_form_with(field: &str, from: Option<&Self>) -> Box<dyn FormState<Self>> {
+        return FromStrFormState::new::<_, Self>(field, "text", from.map(|v| v.to_string()).unwrap_or_default());
+    }
+
+    fn display(field: &str, value: &Self) -> Vec<El> {
+        return vec![display_text(field, &value.to_string())];
+    }
+
+    fn schema(title: &str) -> serde_json::Value {
+        return json!({
+            "type": "integer",
+            "title": title,
+            "minimum": Self::MIN,
+            "maximum": Self::MAX
+        });
+    }
+}
+
+impl Form for i32 {
+    fn new_form_with(field: &str, from: Option<&Self>) -> Box<dyn FormState<Self>> {
+        return FromStrFormState::new::<_, Self>(field, "text", from.map(|v| v.to_string()).unwrap_or_default());
+    }
+
+    fn display(field: &str, value: &Self) -> Vec<El> {
+        return vec![display_text(field, &value.to_string())];
+    }
+
+    fn schema(title: &str) -> serde_json::Value {
+        return json!({
+            "type": "integer",
+            "title": title,
+            "minimum": Self::MIN,
+            "maximum": Self::MAX
+        });
+    }
+}
+
+impl Form for i64 {
+    fn new_form_with(field: &str, from: Option<&Self>) -> Box<dyn FormState<Self>> {
+        return FromStrFormState::new::<_, Self>(field, "text", from.map(|v| v.to_string()).unwrap_or_default());
+    }
+
+    fn display(field: &str, value: &Self) -> Vec<El> {
+        return vec![display_text(field, &value.to_string())];
+    }
+
+    fn schema(title: &str) -> serde_json::Value {
+        return json!({
+            "type": "integer",
+            "title": title,
+            "minimum": Self::MIN,
+            "maximum": Self::MAX
+        });
+    }
+}
+
+impl Form for f32 {
+    fn new_form_with(field: &str, from: Option<&Self>) -> Box<dyn FormState<Self>> {
+        return FromStrFormState::new::<_, Self>(field, "text", from.map(|v| v.to_string()).unwrap_or_default());
+    }
+
+    fn display(field: &str, value: &Self) -> Vec<El> {
+        return vec![display_text(field, &value.to_string())];
+    }
+
+    fn schema(title: &str) -> serde_json::Value {
+        return json!({
+            "type": "number",
+            "title": title
+        });
+    }
+}
+
+impl Form for f64 {
+    fn new_form_with(field: &str, from: Option<&Self>) -> Box<dyn FormState<Self>> {
+        return FromStrFormState::new::<_, Self>(field, "text", from.map(|v| v.to_string()).unwrap_or_default());
+    }
+
+    fn display(field: &str, value: &Self) -> Vec<El> {
+        return vec![display_text(field, &value.to_string())];
+    }
+
+    fn schema(title: &str) -> serde_json::Value {
+        return json!({
+            "type": "number",
+            "title": title
+        });
+    }
+}
+
+struct BoolFormState {
+    input: El,
+    initial: bool,
+    disabled: Disabler,
+}
+
+impl FormState<bool> for BoolFormState {
+    fn elements(&self) -> FormElements {
+        return FormElements {
+            error: None,
+            elements: vec![self.input.clone()],
+        };
+    }
+
+    fn parse(&self) -> Result<bool, ()> {
+        return Ok(dom::checked(&self.input));
+    }
+
+    fn peek(&self) -> Result<bool, ()> {
+        return self.parse();
+    }
+
+    fn on_change(&self, cb: Rc<dyn Fn()>) {
+        self.input.ref_on("change", move |_| cb());
+    }
+
+    fn is_dirty(&self) -> bool {
+        return dom::checked(&self.input) != self.initial;
+    }
+
+    fn reset(&self) {
+        dom::set_checked(&self.input, self.initial);
+    }
+
+    fn set_disabled(&self, disabled: bool) {
+        self.disabled.set(&self.input, disabled);
+    }
+
+    fn get_raw(&self) -> RawState {
+        return RawState::Bool(dom::checked(&self.input));
+    }
+
+    fn set_raw(&self, raw: &RawState) {
+        let RawState::Bool(checked) = raw else {
+            return;
+        };
+        dom::set_checked(&self.input, *checked);
+    }
+
+    fn field(&self, path: &[&str]) -> Option<FieldHandle> {
+        if !path.is_empty() {
+            return None;
+        }
+        return Some(FieldHandle {
+            input: self.input.clone(),
+            error: None,
+            checkbox: true,
+        });
+    }
+
+    fn visit_fields<'a>(&'a self, path: &mut Vec<&'a str>, f: &mut dyn FnMut(&[&'a str], FieldHandle)) {
+        f(path, self.field(&[]).unwrap());
+    }
+
+    fn visit_errors<'a>(&'a self, _path: &mut Vec<&'a str>, _f: &mut dyn FnMut(&[&'a str], FieldHandle)) { }
+}
+
+impl BoolFormState {
+    fn new(label: &str, initial: bool) -> Box<dyn FormState<bool>> {
+        let input = el("input").classes(&[CSS_CLASS_SMALL_INPUT]).attr(ATTR_LABEL, label).attr("type", "checkbox");
+        dom::set_checked(&input, initial);
+        return Box::new(BoolFormState {
+            input: input,
+            initial: initial,
+            disabled: Default::default(),
+        });
+    }
+}
+
+impl Form for bool {
+    fn new_form_with(field: &str, from: Option<&Self>) -> Box<dyn FormState<Self>> {
+        return BoolFormState::new(field, from.cloned().unwrap_or_default());
+    }
+
+    fn display(field: &str, value: &Self) -> Vec<El> {
+        return vec![display_checkbox(field, CSS_CLASS_SMALL_INPUT, *value)];
+    }
+
+    fn schema(title: &str) -> serde_json::Value {
+        return json!({
+            "type": "boolean",
+            "title": title
+        });
+    }
+}
+
+struct OptionFormState<T> {
+    enable: El,
+    additional: Vec<El>,
+    subform: Box<dyn FormState<T>>,
+    initial: bool,
+    disabled: Disabler,
+}
+
+impl<T: 'static> OptionFormState<T> {
+    fn new(label: &str, subform: Box<dyn FormState<T>>, initial: bool) -> Box<dyn FormState<Option<T>>> {
+        let subform_elements = subform.elements();
+        let mut additional = vec![];
+        additional.extend(subform_elements.error.iter().cloned());
+        additional.extend(subform_elements.elements);
+        Self::set_visible(&additional, initial);
+        let enable =
+            el("input")
+                .classes(&[CSS_CLASS_OPTION_ENABLE])
+                .attr(ATTR_LABEL, &format!("{} - Enabled", label))
+                .attr("type", "checkbox");
+        enable.ref_on("click", {
+            let enable = enable.weak();
+            let additional = additional.clone();
+            move |_| {
+                let Some(enable) = enable.upgrade() else {
+                    return;
+                };
+                Self::set_visible(&additional, dom::checked(&enable));
+            }
+        });
+        dom::set_checked(&enable, initial);
+        return Box::new(OptionFormState {
+            enable: enable,
+            additional: additional,
+            subform: subform,
+            initial: initial,
+            disabled: Default::default(),
+        });
+    }
+}
+
+impl<T> OptionFormState<T> {
+    fn set_visible(additional: &Vec<El>, checked: bool) {
+        for e in additional {
+            e.ref_modify_classes(&[(CSS_CLASS_HIDDEN, !checked)]);
+        }
+    }
+}
+
+impl<T> FormState<Option<T>> for OptionFormState<T> {
+    fn elements(&self) -> FormElements {
+        let mut elements = vec![self.enable.clone()];
+        elements.extend(self.additional.iter().cloned());
+        return FormElements {
+            error: None,
+            elements: elements,
+        };
+    }
+
+    fn parse(&self) -> Result<Option<T>, ()> {
+        let checked = dom::checked(&self.enable);
+        if checked {
+            return Ok(Some(self.subform.parse()?));
+        } else {
+            return Ok(None);
+        }
+    }
+
+    fn peek(&self) -> Result<Option<T>, ()> {
+        if dom::checked(&self.enable) {
+            return Ok(Some(self.subform.peek()?));
+        } else {
+            return Ok(None);
+        }
+    }
+
+    fn on_change(&self, cb: Rc<dyn Fn()>) {
+        self.enable.ref_on("change", {
+            let cb = cb.clone();
+            move |_| cb()
+        });
+        self.subform.on_change(cb);
+    }
+
+    fn is_dirty(&self) -> bool {
+        let checked = dom::checked(&self.enable);
+        if checked != self.initial {
+            return true;
+        }
+        return checked && self.subform.is_dirty();
+    }
+
+    fn reset(&self) {
+        dom::set_checked(&self.enable, self.initial);
+        Self::set_visible(&self.additional, self.initial);
+        self.subform.reset();
+    }
+
+    fn set_disabled(&self, disabled: bool) {
+        self.disabled.set(&self.enable, disabled);
+        self.subform.set_disabled(disabled);
+    }
+
+    fn get_raw(&self) -> RawState {
+        return RawState::List(
+            vec![
+                RawState::Bool(dom::checked(&self.enable)),
+                self.subform.get_raw()
+            ],
+        );
+    }
+
+    fn set_raw(&self, raw: &RawState) {
+        let RawState::List(raw) = raw else {
+            return;
+        };
+        let [RawState::Bool(checked), subform] = raw.as_slice() else {
+            return;
+        };
+        dom::set_checked(&self.enable, *checked);
+        Self::set_visible(&self.additional, *checked);
+        self.subform.set_raw(subform);
+    }
+
+    fn field(&self, path: &[&str]) -> Option<FieldHandle> {
+        match path.split_first() {
+            None => return Some(FieldHandle {
+                input: self.enable.clone(),
+                error: None,
+                checkbox: true,
+            }),
+            Some((&"Some", path)) => return self.subform.field(path),
+            Some(_) => return None,
+        }
+    }
+
+    fn visit_fields<'a>(&'a self, path: &mut Vec<&'a str>, f: &mut dyn FnMut(&[&'a str], FieldHandle)) {
+        f(path, self.field(&[]).unwrap());
+        path.push("Some");
+        self.subform.visit_fields(path, f);
+        path.pop();
+    }
+
+    fn visit_errors<'a>(&'a self, path: &mut Vec<&'a str>, f: &mut dyn FnMut(&[&'a str], FieldHandle)) {
+        if dom::checked(&self.enable) {
+            path.push("Some");
+            self.subform.visit_errors(path, f);
+            path.pop();
+        }
+    }
+}
+
+impl<T: Form + 'static> Form for Option<T> {
+    const OPTIONAL: bool = true;
+
+    fn new_form_with(field: &str, from: Option<&Self>) -> Box<dyn FormState<Self>> {
+        let from = from.and_then(|v| v.as_ref());
+        return OptionFormState::new(field, T::new_form_with(field, from), from.is_some());
+    }
+
+    fn display(field: &str, value: &Self) -> Vec<El> {
+        let mut out = vec![display_checkbox(&format!("{} - Enabled", field), CSS_CLASS_OPTION_ENABLE, value.is_some())];
+        if let Some(value) = value {
+            out.extend(T::display(field, value));
+        }
+        return out;
+    }
+
+    fn schema(title: &str) -> serde_json::Value {
+        return json!({
+            "title": title,
+            "anyOf": [T::schema(title), {
+                "type": "null"
+            }]
+        });
+    }
+}
+
+impl<T: Form + 'static> Form for Box<T> {
+    const FIELDSET: bool = T::FIELDSET;
+    const OPTIONAL: bool = T::OPTIONAL;
+
+    fn new_form_with(field: &str, from: Option<&Self>) -> Box<dyn FormState<Self>> {
+        return MapFormState::new(T::new_form_with(field, from.map(|v| v.as_ref())), Box::new);
+    }
+
+    fn display(field: &str, value: &Self) -> Vec<El> {
+        return T::display(field, value);
+    }
+
+    fn schema(title: &str) -> serde_json::Value {
+        return T::schema(title);
+    }
+}
+
+impl<T: Form + 'static> Form for Rc<T> {
+    const FIELDSET: bool = T::FIELDSET;
+    const OPTIONAL: bool = T::OPTIONAL;
+
+    fn new_form_with(field: &str, from: Option<&Self>) -> Box<dyn FormState<Self>> {
+        return MapFormState::new(T::new_form_with(field, from.map(|v| v.as_ref())), Rc::new);
+    }
+
+    fn display(field: &str, value: &Self) -> Vec<El> {
+        return T::display(field, value);
+    }
+
+    fn schema(title: &str) -> serde_json::Value {
+        return T::schema(title);
+    }
+}
+
+impl<T: Form + 'static> Form for Arc<T> {
+    const FIELDSET: bool = T::FIELDSET;
+    const OPTIONAL: bool = T::OPTIONAL;
+
+    fn new_form_with(field: &str, from: Option<&Self>) -> Box<dyn FormState<Self>> {
+        return MapFormState::new(T::new_form_with(field, from.map(|v| v.as_ref())), Arc::new);
+    }
+
+    fn display(field: &str, value: &Self) -> Vec<El> {
+        return T::display(field, value);
+    }
+
+    fn schema(title: &str) -> serde_json::Value {
+        return T::schema(title);
+    }
+}
+
+/// Uses the form of the owned type, ex: `String` for `Cow<'static, str>`. Parsed
+/// values are always `Cow::Owned`.
+impl<B: ToOwned + ?Sized + 'static> Form for Cow<'static, B> where B::Owned: Form + 'static {
+    const FIELDSET: bool = B::Owned::FIELDSET;
+    const OPTIONAL: bool = B::Owned::OPTIONAL;
+
+    fn new_form_with(field: &str, from: Option<&Self>) -> Box<dyn FormState<Self>> {
+        let from = from.map(|v| v.as_ref().to_owned());
+        return MapFormState::new(B::Owned::new_form_with(field, from.as_ref()), Cow::Owned);
+    }
+
+    fn display(field: &str, value: &Self) -> Vec<El> {
+        return B::Owned::display(field, &value.as_ref().to_owned());
+    }
+
+    fn schema(title: &str) -> serde_json::Value {
+        return B::Owned::schema(title);
+    }
+}
+
+impl<T: Form + 'static> Form for Wrapping<T> {
+    const FIELDSET: bool = T::FIELDSET;
+    const OPTIONAL: bool = T::OPTIONAL;
+
+    fn new_form_with(field: &str, from: Option<&Self>) -> Box<dyn FormState<Self>> {
+        return MapFormState::new(T::new_form_with(field, from.map(|v| &v.0)), Wrapping);
+    }
+
+    fn display(field: &str, value: &Self) -> Vec<El> {
+        return T::display(field, &value.0);
+    }
+
+    fn schema(title: &str) -> serde_json::Value {
+        return T::schema(title);
+    }
+}
+
+/// The inputs of a tuple or array, inline in one row. Each element is labeled by
+/// position (`"{field} 1"`, ...) and has its own error, and the element index
+/// (from 0) is its `FormState::field` path segment. The elements are mapped to
+/// `Box<dyn Any>` so that tuples with different element types can share this, and
+/// `assemble` converts them back.
+pub struct RowFormState<T> {
+    row: El,
+    names: Vec<String>,
+    items: Vec<Box<dyn FormState<Box<dyn Any>>>>,
+    assemble: fn(Vec<Box<dyn Any>>) -> T,
+}
+
+impl<T: 'static> RowFormState<T> {
+    pub fn new(
+        label: &str,
+        items: Vec<Box<dyn FormState<Box<dyn Any>>>>,
+        assemble: fn(Vec<Box<dyn Any>>) -> T,
+    ) -> Box<dyn FormState<T>> {
+        let row = el("span").classes(&[CSS_CLASS_ROW]).attr(ATTR_LABEL, label);
+        for item in &items {
+            let elements = item.elements();
+            row.ref_extend(elements.error.into_iter().collect());
+            row.ref_extend(elements.elements);
+        }
+        return Box::new(RowFormState {
+            row: row,
+            names: (0 .. items.len()).map(|i| i.to_string()).collect(),
+            items: items,
+            assemble: assemble,
+        });
+    }
+
+    /// Wraps the form of one element for `new`.
+    pub fn item<A: 'static>(form: Box<dyn FormState<A>>) -> Box<dyn FormState<Box<dyn Any>>> {
+        return MapFormState::new(form, |v| Box::new(v) as Box<dyn Any>);
+    }
+
+    /// Takes the next value in `assemble`, as the type of the corresponding
+    /// element.
+    pub fn take<A: 'static>(values: &mut impl Iterator<Item = Box<dyn Any>>) -> A {
+        return *values.next().unwrap().downcast::<A>().unwrap();
+    }
+}
+
+impl<T> FormState<T> for RowFormState<T> {
+    fn elements(&self) -> FormElements {
+        return FormElements {
+            error: None,
+            elements: vec![self.row.clone()],
+        };
+    }
+
+    fn parse(&self) -> Result<T, ()> {
+        let mut values = vec![];
+        let mut errored = false;
+        for item in &self.items {
+            match item.parse() {
+                Ok(v) => values.push(v),
+                Err(()) => errored = true,
+            }
+        }
+        if errored {
+            return Err(());
+        }
+        return Ok((self.assemble)(values));
+    }
+
+    fn peek(&self) -> Result<T, ()> {
+        let mut values = vec![];
+        let mut errored = false;
+        for item in &self.items {
+            match item.peek() {
+                Ok(v) => values.push(v),
+                Err(()) => errored = true,
+            }
+        }
+        if errored {
+            return Err(());
+        }
+        return Ok((self.assemble)(values));
+    }
+
+    fn on_change(&self, cb: Rc<dyn Fn()>) {
+        for item in &self.items {
+            item.on_change(cb.clone());
+        }
+    }
+
+    fn is_dirty(&self) -> bool {
+        return self.items.iter().any(|i| i.is_dirty());
+    }
+
+    fn reset(&self) {
+        for item in &self.items {
+            item.reset();
+        }
+    }
+
+    fn set_disabled(&self, disabled: bool) {
+        for item in &self.items {
+            item.set_disabled(disabled);
+        }
+    }
+
+    fn get_raw(&self) -> RawState {
+        return RawState::List(self.items.iter().map(|i| i.get_raw()).collect());
+    }
+
+    fn set_raw(&self, raw: &RawState) {
+        let RawState::List(raw) = raw else {
+            return;
+        };
+        if raw.len() != self.items.len() {
+            return;
+        }
+        for (item, raw) in self.items.iter().zip(raw) {
+            item.set_raw(raw);
+        }
+    }
+
+    fn field(&self, path: &[&str]) -> Option<FieldHandle> {
+        let (first, path) = path.split_first()?;
+        return self.items.get(usize::from_str(first).ok()?)?.field(path);
+    }
+
+    fn visit_fields<'a>(&'a self, path: &mut Vec<&'a str>, f: &mut dyn FnMut(&[&'a str], FieldHandle)) {
+        for (name, item) in self.names.iter().zip(&self.items) {
+            path.push(name);
+            item.visit_fields(path, f);
+            path.pop();
+        }
+    }
+
+    fn visit_errors<'a>(&'a self, path: &mut Vec<&'a str>, f: &mut dyn FnMut(&[&'a str], FieldHandle)) {
+        for (name, item) in self.names.iter().zip(&self.items) {
+            path.push(name);
+            item.visit_errors(path, f);
+            path.pop();
+        }
+    }
+}
+
+/// The label of the element at `index` in a tuple or array.
+pub(crate) fn row_label(field: &str, index: usize) -> String {
+    return format!("{} {}", field, index + 1);
+}
+
+/// Read-only elements for a tuple or array, in one row like the form.
+fn display_row(field: &str, elements: Vec<Vec<El>>) -> Vec<El> {
+    return vec![
+        el("span")
+            .classes(&[CSS_CLASS_ROW, CSS_CLASS_DISPLAY])
+            .attr(ATTR_LABEL, field)
+            .extend(elements.into_iter().flatten().collect())
+    ];
+}
+
+/// A schema for a tuple or array with a fixed number of elements.
+fn row_schema(title: &str, items: Vec<serde_json::Value>) -> serde_json::Value {
+    let len = items.len();
+    return json!({
+        "type": "array",
+        "title": title,
+        "prefixItems": items,
+        "items": false,
+        "minItems": len,
+        "maxItems": len
+    });
+}
+
+macro_rules! tuple_form{
+    ($($t: ident $i: tt), +) => {
+        impl<$($t: Form + 'static), +> Form for ($($t,) +) {
+            fn new_form_with(field: &str, from: Option<&Self>) -> Box<dyn FormState<Self>> {
+                return RowFormState::new(
+                    field,
+                    vec![$(RowFormState::<Self>::item($t::new_form_with(&row_label(field, $i), from.map(|v| &v.$i)))), +],
+                    |values| {
+                        let mut values = values.into_iter();
+                        return ($(RowFormState::<Self>::take::<$t>(&mut values),) +);
+                    },
+                );
+            }
+
+            fn display(field: &str, value: &Self) -> Vec<El> {
+                return display_row(field, vec![$($t::display(&row_label(field, $i), &value.$i)), +]);
+            }
+
+            fn schema(title: &str) -> serde_json::Value {
+                return row_schema(title, vec![$($t::schema(&row_label(title, $i))), +]);
+            }
+        }
+    };
+}
+
+tuple_form!(A 0);
+tuple_form!(A 0, B 1);
+tuple_form!(A 0, B 1, C 2);
+tuple_form!(A 0, B 1, C 2, D 3);
+tuple_form!(A 0, B 1, C 2, D 3, E 4);
+tuple_form!(A 0, B 1, C 2, D 3, E 4, F 5);
+tuple_form!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+tuple_form!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+impl<T: Form + 'static, const N: usize> Form for [T; N] {
+    fn new_form_with(field: &str, from: Option<&Self>) -> Box<dyn FormState<Self>> {
+        return RowFormState::new(
+            field,
+            (0 .. N)
+                .map(|i| RowFormState::<Self>::item(T::new_form_with(&row_label(field, i), from.map(|v| &v[i]))))
+                .collect(),
+            |values| {
+                let mut values = values.into_iter();
+                return std::array::from_fn(|_| RowFormState::<Self>::take::<T>(&mut values));
+            },
+        );
+    }
+
+    fn display(field: &str, value: &Self) -> Vec<El> {
+        return display_row(
+            field,
+            value.iter().enumerate().map(|(i, v)| T::display(&row_label(field, i), v)).collect(),
+        );
+    }
+
+    fn schema(title: &str) -> serde_json::Value {
+        return row_schema(title, (0 .. N).map(|i| T::schema(&row_label(title, i))).collect());
+    }
+}
+
+/// One choice for `EnumFormState`.
+pub struct EnumVariant<T> {
+    /// The variant name, used for `FormState::field` paths.
+    pub name: String,
+    /// The text displayed in the `<select>`.
+    pub title: String,
+    /// Builds the inputs for the variant's data. Other than for the initial
+    /// variant, this is called the first time the variant is selected, so
+    /// recursive types don't build forever.
+    pub form: Box<dyn FnOnce() -> Box<dyn FormState<T>>>,
+}
+
+impl<T: 'static> EnumVariant<T> {
+    /// Builds the form with `from` immediately if this is the initial variant,
+    /// otherwise with no existing value when it's first selected.
+    pub fn new(
+        name: &str,
+        title: &str,
+        initial: bool,
+        from: Option<&T>,
+        build: impl Fn(Option<&T>) -> Box<dyn FormState<T>> + 'static,
+    ) -> Self {
+        let form: Box<dyn FnOnce() -> Box<dyn FormState<T>>>;
+        if initial {
+            let built = build(from);
+            form = Box::new(move || built);
+        } else {
+            form = Box::new(move || build(None));
+        }
+        return EnumVariant {
+            name: name.to_string(),
+            title: title.to_string(),
+            form: form,
+        };
+    }
+}
+
+struct EnumVariantState<T> {
+    name: String,
+    build: Cell<Option<Box<dyn FnOnce() -> Box<dyn FormState<T>>>>>,
+    form: OnceCell<Box<dyn FormState<T>>>,
+    /// Holds the form elements once built.
+    container: El,
+}
+
+/// The variants of an `EnumFormState`, shared with the `<select>` listener which
+/// builds them.
+struct EnumVariants<T> {
+    variants: Vec<EnumVariantState<T>>,
+    /// Added to variants as they're built.
+    on_change: RefCell<Vec<Rc<dyn Fn()>>>,
+    disabled: Cell<bool>,
+}
+
+impl<T> EnumVariants<T> {
+    /// The form of a variant, building it if it hasn't been yet.
+    fn form(&self, index: usize) -> &dyn FormState<T> {
+        let v = &self.variants[index];
+        return v.form.get_or_init(|| {
+            let form = (v.build.take().unwrap())();
+            let elements = form.elements();
+            v.container.ref_extend(elements.error.into_iter().collect());
+            v.container.ref_extend(elements.elements);
+            for cb in self.on_change.borrow().iter() {
+                form.on_change(cb.clone());
+            }
+            if self.disabled.get() {
+                form.set_disabled(true);
+            }
+            return form;
+        }).as_ref();
+    }
+
+    fn built(&self) -> impl Iterator<Item = (&str, &dyn FormState<T>)> {
+        return self.variants.iter().filter_map(|v| Some((v.name.as_str(), v.form.get()?.as_ref())));
+    }
+
+    fn set_visible(&self, index: usize) {
+        for (e_index, v) in self.variants.iter().enumerate() {
+            v.container.ref_modify_classes(&[(CSS_CLASS_HIDDEN, e_index != index)]);
+        }
+    }
+}
+
+/// A `<select>` to choose a variant, followed by the elements of each variant's
+/// subform, hidden unless that variant is selected. Used for derived enums.
+/// Variant subforms are built when first selected, and kept when switching to
+/// another variant so that input isn't lost if the user switches back.
+pub struct EnumFormState<T> {
+    select: El,
+    variants: Rc<EnumVariants<T>>,
+    current_variant: Rc<Cell<usize>>,
+    initial_variant: usize,
+    disabled: Disabler,
+}
+
+impl<T: 'static> EnumFormState<T> {
+    /// Returns an error if `variants` is empty, since there'd be nothing to parse.
+    pub fn new(
+        label: &str,
+        initial_variant: usize,
+        variants: Vec<EnumVariant<T>>,
+    ) -> Result<Box<dyn FormState<T>>, String> {
+        if variants.is_empty() {
+            return Err(format!("{} has no variants", label));
+        }
+        let select = el("select").classes(&[CSS_CLASS_SMALL_INPUT]).attr(ATTR_LABEL, label);
+        let mut variant_states = vec![];
+        for (i, v) in variants.into_iter().enumerate() {
+            let option = el("option").text(&v.title).attr("value", &i.to_string());
+            if i == initial_variant {
+                option.ref_attr("selected", "selected");
+            }
+            select.ref_push(option);
+            variant_states.push(EnumVariantState {
+                name: v.name,
+                build: Cell::new(Some(v.form)),
+                form: OnceCell::new(),
+                container: el("div").classes(&[CSS_CLASS_SUBFORM]),
+            });
+        }
+        let variants = Rc::new(EnumVariants {
+            variants: variant_states,
+            on_change: RefCell::new(vec![]),
+            disabled: Cell::new(false),
+        });
+        let current_variant = Rc::new(Cell::new(initial_variant));
+        select.ref_on("change", {
+            let select = select.weak();
+            let variants = variants.clone();
+            let current_variant = current_variant.clone();
+            move |_| {
+                let Some(select) = select.upgrade() else {
+                    return;
+                };
+                let Ok(index) = usize::from_str(&dom::value(&select)) else {
+                    return;
+                };
+                if index >= variants.variants.len() {
+                    return;
+                }
+                variants.form(index);
+                current_variant.set(index);
+                variants.set_visible(index);
+            }
+        });
+        variants.form(initial_variant);
+        variants.set_visible(initial_variant);
+        return Ok(Box::new(EnumFormState {
+            select: select,
+            variants: variants,
+            current_variant: current_variant,
+            initial_variant: initial_variant,
+            disabled: Default::default(),
+        }));
+    }
+}
+
+impl<T> FormState<T> for EnumFormState<T> {
+    fn elements(&self) -> FormElements {
+        let mut out = vec![];
+        out.push(self.select.clone());
+        out.extend(self.variants.variants.iter().map(|v| v.container.clone()));
+        return FormElements {
+            error: None,
+            elements: out,
+        };
+    }
+
+    fn parse(&self) -> Result<T, ()> {
+        return self.variants.form(self.current_variant.get()).parse();
+    }
+
+    fn peek(&self) -> Result<T, ()> {
+        return self.variants.form(self.current_variant.get()).peek();
+    }
+
+    fn on_change(&self, cb: Rc<dyn Fn()>) {
+        self.select.ref_on("change", {
+            let cb = cb.clone();
+            move |_| cb()
+        });
+        for (_, form) in self.variants.built() {
+            form.on_change(cb.clone());
+        }
+        self.variants.on_change.borrow_mut().push(cb);
+    }
+
+    fn is_dirty(&self) -> bool {
+        let current_variant = self.current_variant.get();
+        if current_variant != self.initial_variant {
+            return true;
+        }
+        return self.variants.form(current_variant).is_dirty();
+    }
+
+    fn reset(&self) {
+        dom::set_value(&self.select, &self.initial_variant.to_string());
+        self.current_variant.set(self.initial_variant);
+        self.variants.set_visible(self.initial_variant);
+        for (_, form) in self.variants.built() {
+            form.reset();
+        }
+    }
+
+    fn set_disabled(&self, disabled: bool) {
+        self.disabled.set(&self.select, disabled);
+        self.variants.disabled.set(disabled);
+        for (_, form) in self.variants.built() {
+            form.set_disabled(disabled);
+        }
+    }
+
+    fn get_raw(&self) -> RawState {
+        let mut out = vec![RawState::Text(self.current_variant.get().to_string())];
+        for v in &self.variants.variants {
+            // Variants that haven't been built are left empty
+            out.push(v.form.get().map(|f| f.get_raw()).unwrap_or(RawState::List(vec![])));
+        }
+        return RawState::List(out);
+    }
+
+    fn set_raw(&self, raw: &RawState) {
+        let RawState::List(raw) = raw else {
+            return;
+        };
+        let Some((RawState::Text(index), variants)) = raw.split_first() else {
+            return;
+        };
+        let Ok(index) = usize::from_str(index) else {
+            return;
+        };
+        if index >= self.variants.variants.len() || variants.len() != self.variants.variants.len() {
+            return;
+        }
+        dom::set_value(&self.select, &index.to_string());
+        self.current_variant.set(index);
+        for (i, raw) in variants.iter().enumerate() {
+            let unbuilt = matches!(raw, RawState::List(l) if l.is_empty());
+            if i == index || !unbuilt || self.variants.variants[i].form.get().is_some() {
+                self.variants.form(i).set_raw(raw);
+            }
+        }
+        self.variants.set_visible(index);
+    }
+
+    fn field(&self, path: &[&str]) -> Option<FieldHandle> {
+        let Some((first, path)) = path.split_first() else {
+            return Some(FieldHandle {
+                input: self.select.clone(),
+                error: None,
+                checkbox: false,
+            });
+        };
+        let index = self.variants.variants.iter().position(|v| v.name == *first)?;
+        return self.variants.form(index).field(path);
+    }
+
+    /// Only visits variants that have been built (selected at some point).
+    fn visit_fields<'a>(&'a self, path: &mut Vec<&'a str>, f: &mut dyn FnMut(&[&'a str], FieldHandle)) {
+        f(path, self.field(&[]).unwrap());
+        for (name, form) in self.variants.built() {
+            path.push(name);
+            form.visit_fields(path, f);
+            path.pop();
+        }
+    }
+
+    fn visit_errors<'a>(&'a self, path: &mut Vec<&'a str>, f: &mut dyn FnMut(&[&'a str], FieldHandle)) {
+        let index = self.current_variant.get();
+        path.push(&self.variants.variants[index].name);
+        self.variants.form(index).visit_errors(path, f);
+        path.pop();
+    }
+}
+
+/// One variant of a `FormChoices` enum.
+pub struct Choice<T> {
+    /// The variant name, used for `FormState::field` paths.
+    pub name: &'static str,
+    /// The serialized variant name (the serde rename with `#[form(serde)]`), used
+    /// for the schema.
+    pub key: &'static str,
+    /// The variant `#[title]`.
+    pub title: &'static str,
+    pub value: T,
+}
+
+/// Enums with only unit variants, implemented by `#[derive(Form)]`. This lists the
+/// variants for `CheckboxGroup`.
+pub trait FormChoices: Sized {
+    /// All the variants, in declaration order.
+    fn choices() -> Vec<Choice<Self>>;
+
+    /// The index of this value in `choices`.
+    fn choice_index(&self) -> usize;
+}
+
+/// A widget for sets of `FormChoices` enums (`Vec`, `BTreeSet`, `HashSet`), with
+/// one checkbox per variant. Parsing fails if fewer than `MIN` or more than `MAX`
+/// are checked. Used by `#[form(multi)]` and `#[form(multi(min = 1, max = 3))]`.
+pub struct CheckboxGroup<const MIN: usize = 0, const MAX: usize = { usize::MAX }>;
+
+/// The checkboxes for `CheckboxGroup`.
+pub struct CheckboxGroupFormState<T> {
+    container: El,
+    error_el: El,
+    names: Vec<String>,
+    checkboxes: Vec<Box<dyn FormState<bool>>>,
+    /// All the values, in the same order as `checkboxes`.
+    values: Box<dyn Fn() -> Vec<T>>,
+    min: usize,
+    max: usize,
+    /// The last `parse` failed.
+    invalid: Cell<bool>,
+}
+
+impl<T: FormChoices + 'static> CheckboxGroupFormState<T> {
+    /// * `checked` - the values initially checked
+    pub fn new<'a>(
+        label: &str,
+        checked: impl Iterator<Item = &'a T>,
+        min: usize,
+        max: usize,
+    ) -> Box<dyn FormState<Vec<T>>> {
+        let checked = checked.map(|v| v.choice_index()).collect::<Vec<_>>();
+        let choices = T::choices().into_iter().map(|c| (c.name.to_string(), c.title.to_string())).collect();
+        return Box::new(
+            CheckboxGroupFormState::build(
+                label,
+                choices,
+                &checked,
+                min,
+                max,
+                Box::new(|| T::choices().into_iter().map(|c| c.value).collect()),
+            ),
+        );
+    }
+}
+
+impl<T> CheckboxGroupFormState<T> {
+    /// * `choices` - the name (for paths) and title of each checkbox
+    ///
+    /// * `checked` - the indices of the choices initially checked
+    ///
+    /// * `values` - produces the value for each choice
+    pub(crate) fn build(
+        label: &str,
+        choices: Vec<(String, String)>,
+        checked: &[usize],
+        min: usize,
+        max: usize,
+        values: Box<dyn Fn() -> Vec<T>>,
+    ) -> Self {
+        let container = el("span").classes(&[CSS_CLASS_CHOICES]).attr(ATTR_LABEL, label);
+        let mut names = vec![];
+        let mut checkboxes = vec![];
+        for (i, (name, title)) in choices.into_iter().enumerate() {
+            let checkbox = BoolFormState::new(&title, checked.contains(&i));
+            container.ref_extend(checkbox.elements().elements);
+            container.ref_push(el("span").classes(&[CSS_CLASS_LABEL]).text(&title));
+            names.push(name);
+            checkboxes.push(checkbox);
+        }
+        return CheckboxGroupFormState {
+            container: container,
+            error_el: el("span").classes(&[CSS_CLASS_ERROR]),
+            names: names,
+            checkboxes: checkboxes,
+            values: values,
+            min: min,
+            max: max,
+            invalid: Cell::new(false),
+        };
+    }
+
+    /// The checked values, or the error message if too few or many are checked.
+    fn check(&self) -> Result<Vec<T>, String> {
+        let checked = self.checkboxes.iter().map(|c| c.peek() == Ok(true)).collect::<Vec<_>>();
+        let count = checked.iter().filter(|c| **c).count();
+        if count < self.min {
+            return Err(format!("Select at least {}", self.min));
+        }
+        if count > self.max {
+            return Err(format!("Select at most {}", self.max));
+        }
+        return Ok(
+            (self.values)().into_iter().zip(checked).filter(|(_, checked)| *checked).map(|(v, _)| v).collect(),
+        );
+    }
+}
+
+impl<T> FormState<Vec<T>> for CheckboxGroupFormState<T> {
+    fn elements(&self) -> FormElements {
+        return FormElements {
+            error: Some(self.error_el.clone()),
+            elements: vec![self.container.clone()],
+        };
+    }
+
+    fn parse(&self) -> Result<Vec<T>, ()> {
+        let out = self.check();
+        let error = out.as_ref().err().cloned().unwrap_or_default();
+        self.error_el.ref_text(&error);
+        self.invalid.set(out.is_err());
+        return out.map_err(|_| ());
+    }
+
+    fn peek(&self) -> Result<Vec<T>, ()> {
+        return self.check().map_err(|_| ());
+    }
+
+    fn on_change(&self, cb: Rc<dyn Fn()>) {
+        for c in &self.checkboxes {
+            c.on_change(cb.clone());
+        }
+    }
+
+    fn is_dirty(&self) -> bool {
+        return self.checkboxes.iter().any(|c| c.is_dirty());
+    }
+
+    fn reset(&self) {
+        for c in &self.checkboxes {
+            c.reset();
+        }
+        self.error_el.ref_text("");
+        self.invalid.set(false);
+    }
+
+    fn set_disabled(&self, disabled: bool) {
+        for c in &self.checkboxes {
+            c.set_disabled(disabled);
+        }
+    }
+
+    fn get_raw(&self) -> RawState {
+        return RawState::List(self.checkboxes.iter().map(|c| c.get_raw()).collect());
+    }
+
+    fn set_raw(&self, raw: &RawState) {
+        let RawState::List(raw) = raw else {
+            return;
+        };
+        if raw.len() != self.checkboxes.len() {
+            return;
+        }
+        for (c, raw) in self.checkboxes.iter().zip(raw) {
+            c.set_raw(raw);
+        }
+        self.error_el.ref_text("");
+        self.invalid.set(false);
+    }
+
+    fn field(&self, path: &[&str]) -> Option<FieldHandle> {
+        let (first, path) = path.split_first()?;
+        let index = self.names.iter().position(|n| n == first)?;
+        let mut handle = self.checkboxes[index].field(path)?;
+        handle.error = Some(self.error_el.clone());
+        return Some(handle);
+    }
+
+    fn visit_fields<'a>(&'a self, path: &mut Vec<&'a str>, f: &mut dyn FnMut(&[&'a str], FieldHandle)) {
+        for (name, c) in self.names.iter().zip(&self.checkboxes) {
+            path.push(name);
+            c.visit_fields(path, f);
+            path.pop();
+        }
+    }
+
+    fn visit_errors<'a>(&'a self, path: &mut Vec<&'a str>, f: &mut dyn FnMut(&[&'a str], FieldHandle)) {
+        if !self.invalid.get() {
+            return;
+        }
+        let Some(name) = self.names.first() else {
+            return;
+        };
+        let Some(handle) = self.field(&[name]) else {
+            return;
+        };
+        path.push(name);
+        f(path, handle);
+        path.pop();
+    }
+}
+
+/// The read-only display for `CheckboxGroup`, a disabled checkbox per variant.
+fn display_choices<'a, T: FormChoices + 'a>(field: &str, values: impl Iterator<Item = &'a T>) -> Vec<El> {
+    let checked = values.map(|v| v.choice_index()).collect::<Vec<_>>();
+    let out = el("span").classes(&[CSS_CLASS_CHOICES, CSS_CLASS_DISPLAY]).attr(ATTR_LABEL, field);
+    for (i, choice) in T::choices().into_iter().enumerate() {
+        out.ref_push(display_checkbox(choice.title, CSS_CLASS_SMALL_INPUT, checked.contains(&i)));
+        out.ref_push(el("span").classes(&[CSS_CLASS_LABEL]).text(choice.title));
+    }
+    return vec![out];
+}
+
+fn choices_schema<T: FormChoices>(title: &str, min: usize, max: usize) -> serde_json::Value {
+    let variants = T::choices().into_iter().map(|c| json!({
+        "title": c.title,
+        "const": c.key
+    })).collect::<Vec<_>>();
+    let mut schema = json!({
+        "type": "array",
+        "title": title,
+        "items": {
+            "oneOf": variants
+        },
+        "uniqueItems": true,
+        "minItems": min
+    });
+    if max != usize::MAX {
+        schema["maxItems"] = json!(max);
+    }
+    return schema;
+}
+
+impl<T: FormChoices + 'static, const MIN: usize, const MAX: usize> FormWith<Vec<T>> for CheckboxGroup<MIN, MAX> {
+    fn new_form_with(field: &str, from: Option<&Vec<T>>) -> Box<dyn FormState<Vec<T>>> {
+        return CheckboxGroupFormState::new(field, from.into_iter().flatten(), MIN, MAX);
+    }
+
+    fn display(field: &str, value: &Vec<T>) -> Vec<El> {
+        return display_choices(field, value.iter());
+    }
+
+    fn schema(title: &str) -> serde_json::Value {
+        return choices_schema::<T>(title, MIN, MAX);
+    }
+}
+
+impl<
+    T: FormChoices + Ord + 'static,
+    const MIN: usize,
+    const MAX: usize,
+> FormWith<BTreeSet<T>> for CheckboxGroup<MIN, MAX> {
+    fn new_form_with(field: &str, from: Option<&BTreeSet<T>>) -> Box<dyn FormState<BTreeSet<T>>> {
+        return MapFormState::new(
+            CheckboxGroupFormState::new(field, from.into_iter().flatten(), MIN, MAX),
+            |v| v.into_iter().collect(),
+        );
+    }
+
+    fn display(field: &str, value: &BTreeSet<T>) -> Vec<El> {
+        return display_choices(field, value.iter());
+    }
+
+    fn schema(title: &str) -> serde_json::Value {
+        return choices_schema::<T>(title, MIN, MAX);
+    }
+}
+
+impl<
+    T: FormChoices + Eq + Hash + 'static,
+    const MIN: usize,
+    const MAX: usize,
+> FormWith<HashSet<T>> for CheckboxGroup<MIN, MAX> {
+    fn new_form_with(field: &str, from: Option<&HashSet<T>>) -> Box<dyn FormState<HashSet<T>>> {
+        return MapFormState::new(
+            CheckboxGroupFormState::new(field, from.into_iter().flatten(), MIN, MAX),
+            |v| v.into_iter().collect(),
+        );
+    }
+
+    fn display(field: &str, value: &HashSet<T>) -> Vec<El> {
+        return display_choices(field, value.iter());
+    }
+
+    fn schema(title: &str) -> serde_json::Value {
+        return choices_schema::<T>(title, MIN, MAX);
+    }
+}
+
+/// Adapts the output of a subform, used for tuple enum variants.
+pub struct MapFormState<A, B> {
+    subform: Box<dyn FormState<A>>,
+    map: fn(A) -> B,
+}
+
+impl<A: 'static, B: 'static> MapFormState<A, B> {
+    pub fn new(subform: Box<dyn FormState<A>>, map: fn(A) -> B) -> Box<dyn FormState<B>> {
+        return Box::new(MapFormState {
+            subform: subform,
+            map: map,
+        });
+    }
+}
+
+impl<A, B> FormState<B> for MapFormState<A, B> {
+    fn elements(&self) -> FormElements {
+        return self.subform.elements();
+    }
+
+    fn parse(&self) -> Result<B, ()> {
+        return Ok((self.map)(self.subform.parse()?));
+    }
+
+    fn peek(&self) -> Result<B, ()> {
+        return Ok((self.map)(self.subform.peek()?));
+    }
+
+    fn on_change(&self, cb: Rc<dyn Fn()>) {
+        self.subform.on_change(cb);
+    }
+
+    fn is_dirty(&self) -> bool {
+        return self.subform.is_dirty();
+    }
+
+    fn reset(&self) {
+        self.subform.reset();
+    }
+
+    fn set_disabled(&self, disabled: bool) {
+        self.subform.set_disabled(disabled);
+    }
+
+    fn get_raw(&self) -> RawState {
+        return self.subform.get_raw();
+    }
+
+    fn set_raw(&self, raw: &RawState) {
+        self.subform.set_raw(raw);
+    }
+
+    fn field(&self, path: &[&str]) -> Option<FieldHandle> {
+        return self.subform.field(path);
+    }
+
+    fn visit_fields<'a>(&'a self, path: &mut Vec<&'a str>, f: &mut dyn FnMut(&[&'a str], FieldHandle)) {
+        self.subform.visit_fields(path, f);
+    }
+
+    fn visit_errors<'a>(&'a self, path: &mut Vec<&'a str>, f: &mut dyn FnMut(&[&'a str], FieldHandle)) {
+        self.subform.visit_errors(path, f);
+    }
+}
+
+/// A form with no inputs, used for unit enum variants.
+pub struct UnitFormState<T> {
+    value: Box<dyn Fn() -> T>,
+}
+
+impl<T: 'static> UnitFormState<T> {
+    pub fn new(value: impl Fn() -> T + 'static) -> Box<dyn FormState<T>> {
+        return Box::new(UnitFormState { value: Box::new(value) });
+    }
+}
+
+impl<T> FormState<T> for UnitFormState<T> {
+    fn elements(&self) -> FormElements {
+        return FormElements {
+            error: None,
+            elements: vec![],
+        };
+    }
+
+    fn parse(&self) -> Result<T, ()> {
+        return Ok((self.value)());
+    }
+
+    fn peek(&self) -> Result<T, ()> {
+        return Ok((self.value)());
+    }
+
+    fn on_change(&self, _cb: Rc<dyn Fn()>) { }
+
+    fn is_dirty(&self) -> bool {
+        return false;
+    }
+
+    fn reset(&self) { }
+
+    fn set_disabled(&self, _disabled: bool) { }
+
+    fn get_raw(&self) -> RawState {
+        return RawState::List(vec![]);
+    }
+
+    fn set_raw(&self, _raw: &RawState) { }
+
+    fn field(&self, _path: &[&str]) -> Option<FieldHandle> {
+        return None;
+    }
+
+    fn visit_fields<'a>(&'a self, _path: &mut Vec<&'a str>, _f: &mut dyn FnMut(&[&'a str], FieldHandle)) { }
+
+    fn visit_errors<'a>(&'a self, _path: &mut Vec<&'a str>, _f: &mut dyn FnMut(&[&'a str], FieldHandle)) { }
+}
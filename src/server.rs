@@ -0,0 +1,155 @@
+use std::collections::{
+    HashMap,
+    HashSet,
+};
+use crate::{
+    dom::{
+        self,
+        El,
+        el,
+    },
+    FieldHandle,
+    FormState,
+    CSS_CLASS_FORM,
+    CSS_CLASS_HIDDEN,
+    CSS_CLASS_SUBMIT,
+};
+
+/// A form rendered to static HTML, for pages that work without scripts. Every input
+/// is named with its field path (see `FormState::field`, ex: `address.zip`) so the
+/// `application/x-www-form-urlencoded` body of a `POST` can be parsed back into the
+/// value.
+///
+/// Without scripts, inputs can't be revealed when a checkbox is checked, so all
+/// optional and conditional inputs are shown. They're still ignored when parsing if
+/// their checkbox isn't checked. Likewise the inputs of every enum variant are
+/// built, up to `MAX_ENUM_DEPTH` levels of nested enums (for recursive types).
+pub struct ServerForm<T> {
+    /// The wrapped form state.
+    pub state: Box<dyn FormState<T>>,
+    fields: Vec<(String, FieldHandle)>,
+}
+
+fn decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = vec![];
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                match std::str::from_utf8(&bytes[i + 1 ..= i + 2]).ok().and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                    Some(b) => {
+                        out.push(b);
+                        i += 2;
+                    },
+                    None => out.push(b'%'),
+                }
+            },
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    return String::from_utf8_lossy(&out).into_owned();
+}
+
+/// Split an `application/x-www-form-urlencoded` body into decoded key/value pairs.
+pub fn parse_urlencoded(body: &str) -> Vec<(String, String)> {
+    return body.split('&').filter(|p| !p.is_empty()).map(|p| {
+        let (k, v) = p.split_once('=').unwrap_or((p, ""));
+        return (decode(k), decode(v));
+    }).collect();
+}
+
+/// How many levels of nested enums `ServerForm` builds the variants of.
+pub const MAX_ENUM_DEPTH: usize = 4;
+
+/// Build the subforms of all enum variants by selecting each in turn, since the
+/// variant can't be changed without scripts. Each pass builds the variants of the
+/// enums revealed by the previous pass.
+fn build_variants<T>(state: &dyn FormState<T>) {
+    let mut built = HashSet::new();
+    for _ in 0 .. MAX_ENUM_DEPTH {
+        let mut selects = vec![];
+        state.visit_fields(&mut vec![], &mut |path, f| {
+            if f.input.get_tag() == "select" && built.insert(path.join(".")) {
+                selects.push(f);
+            }
+        });
+        if selects.is_empty() {
+            break;
+        }
+        for f in selects {
+            let current = f.get_value();
+            for (value, _) in dom::options(&f.input) {
+                f.set_value(&value);
+            }
+            f.set_value(&current);
+        }
+    }
+}
+
+fn show_all(elements: &[El]) {
+    for e in elements {
+        e.ref_remove_classes(&[CSS_CLASS_HIDDEN]);
+        show_all(&e.get_children());
+    }
+}
+
+impl<T> ServerForm<T> {
+    /// Wrap a form, naming all of its inputs.
+    pub fn new(state: Box<dyn FormState<T>>) -> Self {
+        build_variants(state.as_ref());
+        let mut fields = vec![];
+        state.visit_fields(&mut vec![], &mut |path, f| {
+            let name = path.join(".");
+            f.input.ref_attr("name", &name);
+            fields.push((name, f));
+        });
+        return ServerForm {
+            state: state,
+            fields: fields,
+        };
+    }
+
+    /// Fill the inputs from an `application/x-www-form-urlencoded` body and parse
+    /// the form. Inputs missing from the body (other than checkboxes) keep their
+    /// current values. A `<select>` value that isn't one of its options is a parse
+    /// failure, and leaves the select unchanged. On failure, the error messages will
+    /// be included in the next `render`.
+    pub fn parse(&self, body: &str) -> Result<T, ()> {
+        let values = parse_urlencoded(body).into_iter().collect::<HashMap<_, _>>();
+        let mut invalid = false;
+        for (name, f) in &self.fields {
+            if f.checkbox {
+                // Unchecked checkboxes are omitted from submissions
+                f.set_value(&values.contains_key(name).to_string());
+            } else if let Some(v) = values.get(name) {
+                if f.input.get_tag() == "select" && !dom::options(&f.input).iter().any(|(value, _)| value == v) {
+                    invalid = true;
+                    continue;
+                }
+                f.set_value(v);
+            }
+        }
+        let out = self.state.parse();
+        if invalid {
+            return Err(());
+        }
+        return out;
+    }
+
+    /// The HTML of a `<form>` that `POST`s to the current URL, containing the form
+    /// elements with their current values and errors followed by a submit button.
+    pub fn render(&self, submit_text: &str) -> String {
+        let elements = self.state.elements();
+        show_all(&elements.elements);
+        return el("form")
+            .classes(&[CSS_CLASS_FORM])
+            .attr("method", "post")
+            .extend(elements.error.into_iter().collect())
+            .extend(elements.elements)
+            .push(el("button").classes(&[CSS_CLASS_SUBMIT]).attr("type", "submit").text(submit_text))
+            .to_html();
+    }
+}
@@ -0,0 +1,91 @@
+use std::{
+    cell::Cell,
+    future::Future,
+    rc::Rc,
+};
+use crate::{
+    dom::{
+        self,
+        El,
+        el,
+    },
+    FormState,
+    CSS_CLASS_FORM,
+    CSS_CLASS_SUBMIT,
+};
+
+/// A complete `<form>` element with a submit button. Submitting (by clicking the
+/// button or pressing enter in an input) parses the form and calls the callback
+/// with the value if there were no validation issues.
+pub struct SubmitForm<T> {
+    /// The `<form>` element, containing the form elements followed by the submit
+    /// button.
+    pub el: El,
+    /// The wrapped form state, ex: for `reset` or `is_dirty`.
+    pub state: Rc<dyn FormState<T>>,
+}
+
+impl<T: 'static> SubmitForm<T> {
+    /// Wrap a form, calling `on_submit` with each successfully parsed value.
+    pub fn new(state: Box<dyn FormState<T>>, submit_text: &str, on_submit: impl Fn(T) + 'static) -> Self {
+        return Self::build(state, submit_text, move |_, _, _, v| on_submit(v));
+    }
+
+    /// Wrap a form, calling `on_submit` with each successfully parsed value. The
+    /// form and submit button are disabled until the returned future completes, and
+    /// further submissions are ignored until then.
+    pub fn new_async<
+        F: Future<Output = ()> + 'static,
+    >(state: Box<dyn FormState<T>>, submit_text: &str, on_submit: impl Fn(T) -> F + 'static) -> Self {
+        return Self::build(state, submit_text, move |state, button, busy, v| {
+            busy.set(true);
+            state.set_disabled(true);
+            button.ref_attr("disabled", "disabled");
+            let f = on_submit(v);
+            let state = state.clone();
+            let button = button.clone();
+            let busy = busy.clone();
+            dom::spawn(async move {
+                f.await;
+                state.set_disabled(false);
+                button.ref_remove_attr("disabled");
+                busy.set(false);
+            });
+        });
+    }
+
+    fn build(
+        state: Box<dyn FormState<T>>,
+        submit_text: &str,
+        on_valid: impl Fn(&Rc<dyn FormState<T>>, &El, &Rc<Cell<bool>>, T) + 'static,
+    ) -> Self {
+        let state: Rc<dyn FormState<T>> = Rc::from(state);
+        let elements = state.elements();
+        let button = el("button").classes(&[CSS_CLASS_SUBMIT]).attr("type", "submit").text(submit_text);
+        let busy = Rc::new(Cell::new(false));
+        let form_el =
+            el("form")
+                .classes(&[CSS_CLASS_FORM])
+                .extend(elements.error.into_iter().collect())
+                .extend(elements.elements)
+                .push(button.clone())
+                .on("submit", {
+                    let state = state.clone();
+                    move |ev| {
+                        ev.prevent_default();
+                        if busy.get() {
+                            return;
+                        }
+                        let Ok(v) = state.parse() else {
+                            state.focus_first_invalid();
+                            return;
+                        };
+                        on_valid(&state, &button, &busy, v);
+                    }
+                });
+        return SubmitForm {
+            el: form_el,
+            state: state,
+        };
+    }
+}
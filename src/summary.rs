@@ -0,0 +1,147 @@
+use std::{
+    collections::HashMap,
+    rc::Rc,
+    sync::atomic::{
+        AtomicUsize,
+        Ordering,
+    },
+};
+use crate::{
+    dom::{
+        self,
+        El,
+        el,
+    },
+    FieldHandle,
+    FormElements,
+    FormState,
+    RawState,
+    ATTR_LABEL,
+    CSS_CLASS_ERROR_SUMMARY,
+    CSS_CLASS_HIDDEN,
+};
+
+/// A list of every validation issue in a form, for placing at the top of long
+/// forms. The list is refilled after each `parse` with one entry per invalid input,
+/// showing the input's title (`aria-label`) and its error message. If other inputs
+/// in the form have the same title, the field path is shown after the title (ex:
+/// `A (alpha.a)`). Clicking an entry focuses the input. The list is hidden while
+/// there are no issues.
+///
+/// This wraps the form state, so use it in place of the original (ex: pass it to
+/// `SubmitForm::new`) after placing `el` on the page.
+pub struct ErrorSummary<T> {
+    /// The `<ul>` element, with `CSS_CLASS_ERROR_SUMMARY`. Each entry is an `<a>`
+    /// linking to the input's `id`. Inputs without an `id` are given one, a prefix
+    /// unique to this summary followed by the field path (see `FormState::field`),
+    /// ex: `form0-alpha.a`.
+    pub el: El,
+    state: Box<dyn FormState<T>>,
+    id_prefix: String,
+}
+
+static NEXT_SUMMARY: AtomicUsize = AtomicUsize::new(0);
+
+impl<T> ErrorSummary<T> {
+    /// Wrap a form.
+    pub fn new(state: Box<dyn FormState<T>>) -> Self {
+        return ErrorSummary {
+            el: el("ul").classes(&[CSS_CLASS_ERROR_SUMMARY, CSS_CLASS_HIDDEN]),
+            state: state,
+            id_prefix: format!("form{}-", NEXT_SUMMARY.fetch_add(1, Ordering::Relaxed)),
+        };
+    }
+
+    fn refresh(&self) {
+        let mut title_counts = HashMap::new();
+        self.state.visit_fields(&mut vec![], &mut |_, f| {
+            if let Some(title) = dom::attr(&f.input, ATTR_LABEL) {
+                *title_counts.entry(title).or_insert(0) += 1;
+            }
+        });
+        let mut entries = vec![];
+        self.state.visit_errors(&mut vec![], &mut |path, f| {
+            let path = path.join(".");
+            let title = match dom::attr(&f.input, ATTR_LABEL) {
+                Some(title) if title_counts.get(&title).copied().unwrap_or(0) > 1 => format!("{} ({})", title, path),
+                Some(title) => title,
+                None => path.clone(),
+            };
+            let text = match f.error.as_ref().map(|e| dom::text(e)).filter(|t| !t.is_empty()) {
+                Some(message) => format!("{}: {}", title, message),
+                None => title,
+            };
+            let id = match dom::attr(&f.input, "id").filter(|id| !id.is_empty()) {
+                Some(id) => id,
+                None => {
+                    let id = format!("{}{}", self.id_prefix, path);
+                    f.input.ref_attr("id", &id);
+                    id
+                },
+            };
+            let input = f.input;
+            entries.push(el("li").push(el("a").attr("href", &format!("#{}", id)).text(&text).on("click", move |ev| {
+                ev.prevent_default();
+                dom::scroll_into_view(&input);
+                dom::focus(&input);
+            })));
+        });
+        self.el.ref_modify_classes(&[(CSS_CLASS_HIDDEN, entries.is_empty())]);
+        self.el.ref_clear();
+        self.el.ref_extend(entries);
+    }
+}
+
+impl<T> FormState<T> for ErrorSummary<T> {
+    fn elements(&self) -> FormElements {
+        return self.state.elements();
+    }
+
+    fn parse(&self) -> Result<T, ()> {
+        let out = self.state.parse();
+        self.refresh();
+        return out;
+    }
+
+    fn peek(&self) -> Result<T, ()> {
+        return self.state.peek();
+    }
+
+    fn on_change(&self, cb: Rc<dyn Fn()>) {
+        self.state.on_change(cb);
+    }
+
+    fn is_dirty(&self) -> bool {
+        return self.state.is_dirty();
+    }
+
+    fn reset(&self) {
+        self.state.reset();
+        self.refresh();
+    }
+
+    fn set_disabled(&self, disabled: bool) {
+        self.state.set_disabled(disabled);
+    }
+
+    fn get_raw(&self) -> RawState {
+        return self.state.get_raw();
+    }
+
+    fn set_raw(&self, raw: &RawState) {
+        self.state.set_raw(raw);
+        self.refresh();
+    }
+
+    fn field(&self, path: &[&str]) -> Option<FieldHandle> {
+        return self.state.field(path);
+    }
+
+    fn visit_fields<'a>(&'a self, path: &mut Vec<&'a str>, f: &mut dyn FnMut(&[&'a str], FieldHandle)) {
+        self.state.visit_fields(path, f);
+    }
+
+    fn visit_errors<'a>(&'a self, path: &mut Vec<&'a str>, f: &mut dyn FnMut(&[&'a str], FieldHandle)) {
+        self.state.visit_errors(path, f);
+    }
+}
@@ -0,0 +1,100 @@
+//! Helpers for driving forms in tests. Paths are `.`-separated `FormState::field`
+//! paths, like `address.zip` or `limit.Some`. Each helper fires the same events a
+//! user's interaction would, so change notifications and conditional fields update
+//! as they would in the browser. The helpers panic if the path doesn't exist.
+//!
+//! ```
+//! use rooting_forms::{
+//!     Form,
+//!     testing::FormTesting,
+//! };
+//!
+//! #[derive(rooting_forms::Form)]
+//! struct Address {
+//!     #[title("Zip")]
+//!     zip: u32,
+//! }
+//!
+//! let form = Address::new_form("Address");
+//! form.fill("zip", "9021O");
+//! assert!(form.parse().is_err());
+//! assert_eq!(form.error_text("zip"), "invalid digit found in string");
+//! form.fill("zip", "90210");
+//! assert_eq!(form.parse().unwrap().zip, 90210);
+//! ```
+use crate::{
+    dom,
+    FieldHandle,
+    FormState,
+};
+
+fn find<T, S: FormState<T> + ?Sized>(state: &S, path: &str) -> FieldHandle {
+    let segments = path.split('.').filter(|s| !s.is_empty()).collect::<Vec<_>>();
+    match state.field(&segments) {
+        Some(f) => return f,
+        None => panic!("No field in form at path [{}]", path),
+    }
+}
+
+pub trait FormTesting<T> {
+    /// Enter `text` in a text input, firing `input` and `change`.
+    fn fill(&self, path: &str, text: &str);
+
+    /// Check or uncheck a checkbox (a `bool` or the enable checkbox of an
+    /// `Option`), firing `click` and `change`.
+    fn toggle(&self, path: &str, checked: bool);
+
+    /// Pick the enum variant with title `title` in a variant `<select>`, firing
+    /// `change`.
+    fn choose(&self, path: &str, title: &str);
+
+    /// Fire an arbitrary event at the input at `path`.
+    fn fire(&self, path: &str, event: &str);
+
+    /// The validation error currently displayed for the input at `path`, empty if
+    /// there's no error.
+    fn error_text(&self, path: &str) -> String;
+}
+
+impl<T, S: FormState<T> + ?Sized> FormTesting<T> for S {
+    fn fill(&self, path: &str, text: &str) {
+        let f = find(self, path);
+        if f.checkbox {
+            panic!("Field at path [{}] is a checkbox, use `toggle`", path);
+        }
+        f.set_value(text);
+    }
+
+    fn toggle(&self, path: &str, checked: bool) {
+        let f = find(self, path);
+        if !f.checkbox {
+            panic!("Field at path [{}] isn't a checkbox", path);
+        }
+        f.set_value(&checked.to_string());
+    }
+
+    fn choose(&self, path: &str, title: &str) {
+        let f = find(self, path);
+        let options = dom::options(&f.input);
+        let Some((value, _)) = options.iter().find(|(_, text)| text == title) else {
+            panic!(
+                "No option [{}] at path [{}], options are {:?}",
+                title,
+                path,
+                options.iter().map(|(_, text)| text).collect::<Vec<_>>()
+            );
+        };
+        f.set_value(value);
+    }
+
+    fn fire(&self, path: &str, event: &str) {
+        dom::dispatch(&find(self, path).input, event);
+    }
+
+    fn error_text(&self, path: &str) -> String {
+        let Some(error) = find(self, path).error else {
+            panic!("Field at path [{}] doesn't display errors", path);
+        };
+        return dom::text(&error);
+    }
+}
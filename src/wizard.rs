@@ -0,0 +1,114 @@
+use std::{
+    cell::Cell,
+    rc::Rc,
+};
+use crate::{
+    dom::{
+        El,
+        el,
+    },
+    FormState,
+    FormSteps,
+    CSS_CLASS_BACK,
+    CSS_CLASS_FORM,
+    CSS_CLASS_HIDDEN,
+    CSS_CLASS_SUBFORM,
+    CSS_CLASS_SUBMIT,
+};
+
+/// Labels for the `Wizard` buttons.
+pub struct WizardText<'a> {
+    pub back: &'a str,
+    pub next: &'a str,
+    pub finish: &'a str,
+}
+
+/// A `<form>` that shows one step of a `FormSteps` struct at a time. Each step is
+/// in a `<fieldset>` with the step title as the `<legend>`. Moving to the next
+/// step only validates the current step's fields, and the whole struct is parsed
+/// on the last step.
+pub struct Wizard<T> {
+    /// The `<form>` element, containing the steps followed by the back and
+    /// next/finish buttons.
+    pub el: El,
+    /// The wrapped form state, ex: for `reset` or `is_dirty`.
+    pub state: Rc<dyn FormState<T>>,
+}
+
+impl<T: FormSteps + 'static> Wizard<T> {
+    /// Create a wizard, with the inputs initially set from `from` if present.
+    /// `on_finish` is called with the value when the last step is submitted with no
+    /// validation issues.
+    pub fn new(from: Option<&T>, text: WizardText, on_finish: impl Fn(T) + 'static) -> Self {
+        let (state, steps) = T::new_steps_form(from);
+        let state: Rc<dyn FormState<T>> = Rc::from(state);
+        let current = Rc::new(Cell::new(0usize));
+        let step_els =
+            steps
+                .iter()
+                .map(
+                    |s| el("fieldset")
+                        .classes(&[CSS_CLASS_SUBFORM])
+                        .push(el("legend").text(s.title))
+                        .extend(s.elements.clone()),
+                )
+                .collect::<Vec<_>>();
+        let back = el("button").classes(&[CSS_CLASS_BACK]).attr("type", "button").text(text.back);
+        let next = el("button").classes(&[CSS_CLASS_SUBMIT]).attr("type", "submit");
+        let show = Rc::new({
+            let step_els = step_els.clone();
+            let back = back.clone();
+            let next = next.clone();
+            let next_text = text.next.to_string();
+            let finish_text = text.finish.to_string();
+            move |index: usize| {
+                for (i, e) in step_els.iter().enumerate() {
+                    e.ref_modify_classes(&[(CSS_CLASS_HIDDEN, i != index)]);
+                }
+                back.ref_modify_classes(&[(CSS_CLASS_HIDDEN, index == 0)]);
+                if index + 1 == step_els.len() {
+                    next.ref_text(&finish_text);
+                } else {
+                    next.ref_text(&next_text);
+                }
+            }
+        });
+        show(0);
+        back.ref_on("click", {
+            let current = current.clone();
+            let show = show.clone();
+            move |_| {
+                let Some(index) = current.get().checked_sub(1) else {
+                    return;
+                };
+                current.set(index);
+                show(index);
+            }
+        });
+        let form_el = el("form").classes(&[CSS_CLASS_FORM]).extend(step_els).push(back).push(next).on("submit", {
+            let state = state.clone();
+            move |ev| {
+                ev.prevent_default();
+                let index = current.get();
+                if !(steps[index].validate)() {
+                    state.focus_first_invalid();
+                    return;
+                }
+                if index + 1 < steps.len() {
+                    current.set(index + 1);
+                    show(index + 1);
+                    return;
+                }
+                let Ok(v) = state.parse() else {
+                    state.focus_first_invalid();
+                    return;
+                };
+                on_finish(v);
+            }
+        });
+        return Wizard {
+            el: form_el,
+            state: state,
+        };
+    }
+}
@@ -1,10 +1,30 @@
-#[derive(rooting_forms::Form)]
+use rooting_forms::{
+    dom::{
+        self,
+        El,
+    },
+    draft::Draft,
+    submit::SubmitForm,
+    wizard::{
+        Wizard,
+        WizardText,
+    },
+    summary::ErrorSummary,
+    Form,
+    FormState,
+    FormSteps,
+    testing::FormTesting,
+};
+
+/// Holds a number.
+#[derive(rooting_forms::Form, Debug, PartialEq)]
 pub struct Alpha {
+    /// Any number.
     #[title("A")]
     pub a: i32,
 }
 
-#[derive(rooting_forms::Form)]
+#[derive(rooting_forms::Form, Debug, PartialEq)]
 pub enum Beta {
     #[title("A")]
     A,
@@ -16,3 +36,1202 @@ pub enum Beta {
         nix: i32,
     },
 }
+
+#[derive(rooting_forms::Form)]
+pub struct Gamma {
+    #[title("Alpha")]
+    pub alpha: Alpha,
+    #[title("Flat alpha")]
+    #[form(flatten)]
+    pub flat_alpha: Alpha,
+}
+
+#[derive(rooting_forms::Form)]
+pub struct Delta {
+    #[title("Maybe number")]
+    pub number: Option<i32>,
+    #[title("Maybe beta")]
+    pub beta: Option<Beta>,
+}
+
+fn is_positive(v: &i32) -> bool {
+    return *v > 0;
+}
+
+#[derive(rooting_forms::Form)]
+pub struct Zeta {
+    #[title("Use auth")]
+    pub use_auth: bool,
+    #[title("SMTP password")]
+    #[form(visible_if = "use_auth")]
+    pub smtp_password: rooting_forms::Password,
+    #[title("Retries")]
+    pub retries: i32,
+    #[title("Retry delay")]
+    #[form(visible_if(retries = "is_positive"))]
+    pub retry_delay: u32,
+}
+
+#[derive(rooting_forms::Form)]
+pub struct Eta {
+    #[title("Username")]
+    #[form(step = "Account")]
+    pub username: String,
+    #[title("Password")]
+    pub password: rooting_forms::Password,
+    #[title("Age")]
+    pub age: u8,
+    #[title("Display name")]
+    #[form(step = "Profile")]
+    pub display_name: String,
+    #[title("Lucky number")]
+    pub lucky_number: u8,
+    #[title("Show display name")]
+    pub show_display_name: bool,
+    #[title("Bio")]
+    #[form(visible_if = "show_display_name")]
+    pub bio: rooting_forms::BigString,
+}
+
+#[derive(rooting_forms::Form, Debug, PartialEq)]
+pub enum Expr {
+    #[title("Literal")]
+    Lit(i64),
+    #[title("Negate")]
+    Neg(Box<Expr>),
+    #[title("Add")]
+    Add {
+        #[title("Left")]
+        left: Box<Expr>,
+        #[title("Right")]
+        right: Box<Expr>,
+    },
+}
+
+#[derive(rooting_forms::Form, Debug, PartialEq)]
+pub struct Iota {
+    #[title("Shared")]
+    pub shared: std::rc::Rc<Alpha>,
+    #[title("Count")]
+    pub count: std::sync::Arc<i32>,
+    #[title("Name")]
+    pub name: std::borrow::Cow<'static, str>,
+    #[title("Counter")]
+    pub counter: std::num::Wrapping<u8>,
+}
+
+#[derive(rooting_forms::Form, Debug, PartialEq)]
+pub struct Kappa {
+    #[title("Point")]
+    pub point: (f64, f64),
+    #[title("IP")]
+    pub ip: [u8; 4],
+}
+
+/// Uppercases whatever is entered.
+pub struct Upper;
+
+impl rooting_forms::FormWith<String> for Upper {
+    fn new_form_with(
+        field: &str,
+        from: Option<&String>,
+    ) -> Box<dyn rooting_forms::FormState<String>> {
+        return rooting_forms::MapFormState::new(String::new_form_with(field, from), |v| v.to_uppercase());
+    }
+
+    fn display(field: &str, value: &String) -> Vec<El> {
+        return String::display(field, &value.to_uppercase());
+    }
+}
+
+#[derive(rooting_forms::Form)]
+pub struct Theta {
+    #[title("Password")]
+    #[form(with = rooting_forms::Password)]
+    pub password: String,
+    #[title("Notes")]
+    #[form(with = rooting_forms::BigString)]
+    pub notes: String,
+    #[title("Code")]
+    #[form(with = Upper)]
+    pub code: String,
+}
+
+#[derive(rooting_forms::Form, Debug, PartialEq)]
+pub enum Credential {
+    #[title("Password")]
+    Password(#[form(with = rooting_forms::Password)] String),
+    #[title("Key")]
+    Key(#[form(with = rooting_forms::BigString)] String),
+}
+
+#[derive(rooting_forms::Form, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum Permission {
+    #[title("Read")]
+    Read,
+    #[title("Write")]
+    Write,
+    #[title("Admin")]
+    Admin,
+}
+
+#[derive(rooting_forms::Form, Debug, PartialEq)]
+pub struct Lambda {
+    #[title("Permissions")]
+    #[form(multi)]
+    pub permissions: std::collections::BTreeSet<Permission>,
+    #[title("Notify")]
+    #[form(multi(min = 1, max = 2))]
+    pub notify: Vec<Permission>,
+}
+
+/// Stands in for an enum from another crate, which can't derive `Form`.
+#[derive(Debug, PartialEq, Clone, Copy, strum::Display, strum::EnumString, strum::EnumIter)]
+pub enum Weekday {
+    Mon,
+    Tue,
+    Wed,
+}
+
+#[derive(rooting_forms::Form, Debug, PartialEq)]
+pub struct Mu {
+    #[title("Day")]
+    #[form(with = WeekdaySelect)]
+    pub day: Weekday,
+    #[cfg(feature = "strum")]
+    #[title("Backup day")]
+    #[form(with = rooting_forms::Select)]
+    pub backup_day: Weekday,
+}
+
+pub struct WeekdaySelect;
+
+impl rooting_forms::FormWith<Weekday> for WeekdaySelect {
+    fn new_form_with(field: &str, from: Option<&Weekday>) -> Box<dyn rooting_forms::FormState<Weekday>> {
+        return rooting_forms::SelectFormState::new(field, [Weekday::Mon, Weekday::Tue, Weekday::Wed], from);
+    }
+
+    fn display(field: &str, value: &Weekday) -> Vec<El> {
+        return vec![rooting_forms::display_text(field, &value.to_string())];
+    }
+
+    fn schema(title: &str) -> rooting_forms::serde_json::Value {
+        return rooting_forms::select_schema(title, [Weekday::Mon, Weekday::Tue, Weekday::Wed]);
+    }
+}
+
+fn default_port() -> u16 {
+    return 8080;
+}
+
+#[derive(rooting_forms::Form, serde::Deserialize, Debug, PartialEq, Default)]
+#[form(serde)]
+pub struct Listen {
+    #[serde(rename = "Host")]
+    pub host: String,
+    #[serde(default = "default_port")]
+    #[title("Port")]
+    pub port: u16,
+}
+
+#[derive(rooting_forms::Form, serde::Deserialize, Debug, PartialEq)]
+#[form(serde)]
+pub struct Nu {
+    #[serde(flatten)]
+    pub listen: Listen,
+    #[serde(rename(deserialize = "Workers"), default)]
+    pub workers: u8,
+    #[serde(skip)]
+    pub cache: Vec<String>,
+    #[title("Mode")]
+    pub mode: Mode,
+}
+
+#[derive(rooting_forms::Form, serde::Deserialize, Debug, PartialEq)]
+#[form(serde)]
+pub enum Mode {
+    #[serde(rename = "Fast mode")]
+    Fast,
+    #[serde(rename = "Careful mode")]
+    Careful {
+        #[serde(rename = "Retries")]
+        retries: u8,
+        #[serde(skip, default = "default_port")]
+        fallback_port: u16,
+    },
+}
+
+#[derive(rooting_forms::Form, serde::Deserialize, Debug, PartialEq)]
+#[form(serde)]
+pub struct Omicron {
+    #[title("Custom port")]
+    pub custom_port: bool,
+    #[title("Port")]
+    #[form(visible_if = "custom_port")]
+    #[serde(default = "default_port")]
+    pub port: u16,
+}
+
+#[derive(rooting_forms::Form, serde::Deserialize, Debug, PartialEq)]
+#[form(serde)]
+#[serde(default)]
+pub struct Pi {
+    #[title("Name")]
+    pub name: String,
+    #[title("Custom port")]
+    pub custom_port: bool,
+    #[title("Port")]
+    #[form(visible_if = "custom_port")]
+    pub port: u16,
+    #[serde(skip)]
+    pub cache: Vec<String>,
+}
+
+impl Default for Pi {
+    fn default() -> Self {
+        return Pi {
+            name: "server".to_string(),
+            custom_port: false,
+            port: 8080,
+            cache: vec!["warm".to_string()],
+        };
+    }
+}
+
+#[derive(rooting_forms::Form, Debug, PartialEq)]
+pub struct Limits {
+    #[title("Min")]
+    pub min: u8,
+    #[title("Max")]
+    pub max: u8,
+}
+
+impl Limits {
+    fn any(&self) -> bool {
+        return self.max > 0;
+    }
+}
+
+#[derive(rooting_forms::Form, Debug, PartialEq)]
+pub struct Rho {
+    #[title("Limits")]
+    pub limits: Limits,
+    #[title("Note")]
+    #[form(visible_if(limits = "Limits::any"))]
+    pub note: String,
+}
+
+#[derive(rooting_forms::Form, Debug, PartialEq)]
+pub struct Xi {
+    #[title("Point")]
+    pub point: (f64, f64),
+    #[title("IP")]
+    pub ip: [u8; 4],
+    #[title("Permissions")]
+    #[form(multi(min = 1))]
+    pub permissions: std::collections::BTreeSet<Permission>,
+}
+
+fn find_all(elements: &[El], tag: &str, out: &mut Vec<El>) {
+    for e in elements {
+        if e.get_tag() == tag {
+            out.push(e.clone());
+        }
+        find_all(&e.get_children(), tag, out);
+    }
+}
+
+fn find(elements: &[El], tag: &str) -> Vec<El> {
+    let mut out = vec![];
+    find_all(elements, tag, &mut out);
+    return out;
+}
+
+#[test]
+fn parse_struct() {
+    let form = Alpha::new_form("Alpha");
+    let elements = form.elements().elements;
+    let input = &find(&elements, "input")[0];
+    dom::set_value(input, "x");
+    assert!(form.parse().is_err());
+    assert_eq!(elements[0].get_text(), "invalid digit found in string");
+    dom::set_value(input, "12");
+    assert_eq!(form.parse(), Ok(Alpha { a: 12 }));
+}
+
+#[test]
+fn parse_enum() {
+    let form = Beta::new_form("Beta");
+    let elements = form.elements().elements;
+    assert_eq!(form.parse(), Ok(Beta::A));
+    let select = &find(&elements, "select")[0];
+    dom::set_value(select, "1");
+    dom::dispatch(select, "change");
+    dom::set_value(&find(&elements, "input")[0], "5");
+    assert_eq!(form.parse(), Ok(Beta::B(5)));
+}
+
+#[test]
+fn seed_dirty_reset() {
+    let form = Beta::new_form_with("Beta", Some(&Beta::C { nix: 4 }));
+    let elements = form.elements().elements;
+    assert_eq!(form.parse(), Ok(Beta::C { nix: 4 }));
+    assert!(!form.is_dirty());
+    let select = &find(&elements, "select")[0];
+    dom::set_value(select, "0");
+    dom::dispatch(select, "change");
+    assert!(form.is_dirty());
+    assert_eq!(form.parse(), Ok(Beta::A));
+    form.reset();
+    assert!(!form.is_dirty());
+    assert_eq!(form.parse(), Ok(Beta::C { nix: 4 }));
+}
+
+#[test]
+fn fill_by_path() {
+    let form = Gamma::new_form("Gamma");
+    form.fill("alpha.a", "x");
+    form.fill("flat_alpha.a", "3");
+    assert!(form.parse().is_err());
+    assert_eq!(form.error_text("alpha.a"), "invalid digit found in string");
+    assert_eq!(form.error_text("flat_alpha.a"), "");
+    form.fill("alpha.a", "2");
+    let gamma = form.parse().unwrap();
+    assert_eq!(gamma.alpha, Alpha { a: 2 });
+    assert_eq!(gamma.flat_alpha, Alpha { a: 3 });
+    let form = Delta::new_form("Delta");
+    form.toggle("number", true);
+    form.fill("number.Some", "4");
+    form.toggle("beta", true);
+    form.choose("beta.Some", "C");
+    form.fill("beta.Some.C.nix", "7");
+    let delta = form.parse().unwrap();
+    assert_eq!(delta.number, Some(4));
+    assert_eq!(delta.beta, Some(Beta::C { nix: 7 }));
+    form.toggle("number", false);
+    assert_eq!(form.parse().unwrap().number, None);
+}
+
+#[test]
+fn field_handle() {
+    let form = Zeta::new_form("Zeta");
+    let use_auth = form.field(&["use_auth"]).unwrap();
+    assert_eq!(use_auth.get_value(), "false");
+    use_auth.set_value("true");
+    assert_eq!(use_auth.get_value(), "true");
+    let retries = form.field(&["retries"]).unwrap();
+    retries.set_value("3");
+    assert_eq!(retries.get_value(), "3");
+    form.field(&["retry_delay"]).unwrap().set_value("10");
+    assert!(form.field(&["retries", "x"]).is_none());
+    assert!(form.field(&["nothing"]).is_none());
+    let zeta = form.parse().unwrap();
+    assert!(zeta.use_auth);
+    assert_eq!(zeta.retries, 3);
+}
+
+/// Registers a callback on the form that counts how many times it's called.
+fn count_changes<T>(form: &dyn FormState<T>) -> std::rc::Rc<std::cell::Cell<usize>> {
+    let count = std::rc::Rc::new(std::cell::Cell::new(0));
+    form.on_change(std::rc::Rc::new({
+        let count = count.clone();
+        move || count.set(count.get() + 1)
+    }));
+    return count;
+}
+
+#[test]
+fn on_change() {
+    let form = Zeta::new_form("Zeta");
+    let count = count_changes(form.as_ref());
+    form.fill("retries", "3");
+    assert_eq!(count.get(), 1);
+    form.toggle("use_auth", true);
+    assert_eq!(count.get(), 2);
+    let form = Delta::new_form("Delta");
+    let count = count_changes(form.as_ref());
+    form.toggle("number", true);
+    assert_eq!(count.get(), 1);
+    form.fill("number.Some", "4");
+    assert_eq!(count.get(), 2);
+    form.toggle("beta", true);
+    assert_eq!(count.get(), 3);
+    form.choose("beta.Some", "C");
+    assert_eq!(count.get(), 4);
+    // Built by the selection above, after the callback was registered
+    form.fill("beta.Some.C.nix", "5");
+    assert_eq!(count.get(), 5);
+}
+
+#[test]
+fn visible_if() {
+    let form = Zeta::new_form("Zeta");
+    let hidden = |path: &str| form.field(&[path]).unwrap().input.has_class(rooting_forms::CSS_CLASS_HIDDEN);
+    assert!(hidden("smtp_password"));
+    assert!(hidden("retry_delay"));
+    form.toggle("use_auth", true);
+    assert!(!hidden("smtp_password"));
+    form.fill("smtp_password", "hunter2");
+    // Checking the predicate while typing (before `change`) doesn't show the
+    // sibling's errors
+    dom::set_value(&form.field(&["retries"]).unwrap().input, "x");
+    form.fire("retries", "input");
+    assert_eq!(form.error_text("retries"), "");
+    assert!(hidden("retry_delay"));
+    form.fill("retries", "3");
+    assert!(!hidden("retry_delay"));
+    form.fill("retry_delay", "x");
+    assert!(form.parse().is_err());
+    assert_eq!(form.error_text("retry_delay"), "invalid digit found in string");
+    form.fill("retries", "0");
+    form.toggle("use_auth", false);
+    assert!(hidden("retry_delay"));
+    let zeta = form.parse().unwrap();
+    assert_eq!(zeta.retry_delay, 0);
+    form.reset();
+    assert!(hidden("smtp_password"));
+    let form = Omicron::new_form("Omicron");
+    assert_eq!(form.parse().unwrap(), Omicron {
+        custom_port: false,
+        port: 8080,
+    });
+    // Checking visibility doesn't clear errors in a struct sibling
+    let form = Rho::new_form("Rho");
+    let hidden = || form.field(&["note"]).unwrap().input.has_class(rooting_forms::CSS_CLASS_HIDDEN);
+    form.fill("limits.min", "x");
+    form.fill("limits.max", "3");
+    assert!(form.parse().is_err());
+    form.fill("limits.max", "4");
+    assert!(hidden());
+    assert_eq!(form.error_text("limits.min"), "invalid digit found in string");
+    let mut invalid = vec![];
+    form.visit_errors(&mut vec![], &mut |path, _| invalid.push(path.join(".")));
+    assert_eq!(invalid, vec!["limits.min"]);
+    form.fill("limits.min", "1");
+    assert!(!hidden());
+}
+
+#[test]
+fn server_render_parse() {
+    let form = rooting_forms::server::ServerForm::new(Delta::new_form("Delta"));
+    let html = form.render("Save");
+    assert!(html.starts_with("<form class=\"form\" method=\"post\">"));
+    assert!(html.contains("name=\"number\""));
+    assert!(html.contains("name=\"number.Some\""));
+    assert!(html.contains("name=\"beta.Some.C.nix\""));
+    assert!(!html.contains("disable_hide"));
+    assert!(form.parse("number=on&number.Some=x&beta.Some=0").is_err());
+    let html = form.render("Save");
+    assert!(html.contains("invalid digit found in string"));
+    assert!(html.contains("value=\"x\""));
+    let delta = form.parse("number=on&number.Some=12&beta=on&beta.Some=2&beta.Some.C.nix=%2D3").unwrap();
+    assert_eq!(delta.number, Some(12));
+    assert_eq!(delta.beta, Some(Beta::C { nix: -3 }));
+    assert!(form.render("Save").contains("<option value=\"2\" selected=\"selected\">C</option>"));
+    assert_eq!(form.parse("").unwrap().number, None);
+    // Values that aren't options are rejected rather than selected
+    assert!(form.parse("beta=on&beta.Some=7").is_err());
+    assert!(form.render("Save").contains("<option value=\"2\" selected=\"selected\">C</option>"));
+    let form = rooting_forms::server::ServerForm::new(Expr::new_form("Expr"));
+    assert!(form.parse("=7").is_err());
+    assert!(form.parse("=x").is_err());
+    // Out of range variants are ignored by the select too
+    form.state.field(&[]).unwrap().set_value("7");
+    assert!(form.parse("Lit=1").is_ok());
+}
+
+#[test]
+fn schema() {
+    use rooting_forms::serde_json::json;
+
+    assert_eq!(Alpha::schema("Alpha"), json!({
+        "type": "object",
+        "title": "Alpha",
+        "description": "Holds a number.",
+        "properties": {
+            "a": {
+                "type": "integer",
+                "title": "A",
+                "description": "Any number.",
+                "minimum": i32::MIN,
+                "maximum": i32::MAX
+            }
+        },
+        "required": ["a"],
+        "additionalProperties": false
+    }));
+    let delta = Delta::schema("Delta");
+    assert_eq!(delta["required"], json!([]));
+    assert_eq!(delta["properties"]["number"]["anyOf"][1], json!({
+        "type": "null"
+    }));
+    let beta = &delta["properties"]["beta"]["anyOf"][0];
+    assert_eq!(beta["oneOf"][0], json!({
+        "title": "A",
+        "const": "A"
+    }));
+    assert_eq!(beta["oneOf"][1]["properties"]["B"]["type"], "integer");
+    assert_eq!(beta["oneOf"][2]["properties"]["C"]["properties"]["nix"]["title"], "Something");
+}
+
+#[test]
+fn dynamic_form() {
+    use rooting_forms::{
+        dynamic::DynamicForm,
+        serde_json::json,
+    };
+
+    let form = DynamicForm::new("Delta", &Delta::schema("Delta"), None).unwrap();
+    assert_eq!(form.parse(), Ok(json!({})));
+    form.toggle("number", true);
+    form.fill("number.Some", "x");
+    assert!(form.parse().is_err());
+    assert_eq!(form.error_text("number.Some"), "invalid digit found in string");
+    form.fill("number.Some", "4");
+    form.toggle("beta", true);
+    form.choose("beta.Some", "C");
+    form.fill("beta.Some.C.C.nix", "7");
+    assert_eq!(form.parse(), Ok(json!({
+        "number": 4,
+        "beta": {
+            "C": {
+                "nix": 7
+            }
+        }
+    })));
+    let form = DynamicForm::new("Beta", &Beta::schema("Beta"), Some(&json!({
+        "B": 3
+    }))).unwrap();
+    assert_eq!(form.parse(), Ok(json!({
+        "B": 3
+    })));
+    form.choose("", "A");
+    assert_eq!(form.parse(), Ok(json!("A")));
+    assert!(DynamicForm::new("List", &json!({
+        "type": "array"
+    }), None).is_err());
+    assert!(DynamicForm::new("Nothing", &json!({
+        "oneOf": []
+    }), None).is_err());
+}
+
+#[test]
+fn dynamic_arrays() {
+    use rooting_forms::{
+        dynamic::DynamicForm,
+        serde_json::json,
+    };
+
+    let form = DynamicForm::new("Xi", &Xi::schema("Xi"), Some(&json!({
+        "point": [1.5, 2],
+        "ip": [10, 0, 0, 1],
+        "permissions": ["Write"]
+    }))).unwrap();
+    assert_eq!(form.field(&["permissions", "Write"]).unwrap().get_value(), "true");
+    form.fill("ip.3", "2");
+    form.toggle("permissions.Write", false);
+    assert!(form.parse().is_err());
+    assert_eq!(form.error_text("permissions.Read"), "Select at least 1");
+    form.toggle("permissions.Admin", true);
+    assert_eq!(form.parse(), Ok(json!({
+        "point": [1.5, 2.0],
+        "ip": [10, 0, 0, 2],
+        "permissions": ["Admin"]
+    })));
+
+    let form = DynamicForm::new("Tags", &json!({
+        "type": "array",
+        "title": "Tags",
+        "items": {
+            "type": "string",
+            "title": "Tag"
+        },
+        "minItems": 1
+    }), Some(&json!(["a", "b", "c"]))).unwrap();
+    let count = count_changes(form.as_ref());
+    let elements = form.elements().elements;
+    assert!(!form.is_dirty());
+    let remove = find(&elements, "button").into_iter().filter(|b| b.has_class(rooting_forms::CSS_CLASS_LIST_REMOVE)).collect::<Vec<_>>();
+    let add = find(&elements, "button").into_iter().find(|b| b.has_class(rooting_forms::CSS_CLASS_LIST_ADD)).unwrap();
+    form.fill("1", "x");
+    dom::dispatch(&remove[0], "click");
+    assert_eq!(form.parse(), Ok(json!(["x", "c"])));
+    assert!(form.field(&["2"]).is_none());
+    assert!(form.is_dirty());
+    dom::dispatch(&add, "click");
+    assert_eq!(form.field(&["2"]).unwrap().get_value(), "");
+    form.fill("2", "d");
+    assert_eq!(form.parse(), Ok(json!(["x", "c", "d"])));
+    // Rows built after `on_change` was registered notify too
+    dom::dispatch(&add, "click");
+    form.fill("3", "e");
+    assert_eq!(count.get(), 6);
+    assert_eq!(form.parse(), Ok(json!(["x", "c", "d", "e"])));
+    form.set_raw(&rooting_forms::RawState::List(vec![]));
+    assert!(form.parse().is_err());
+    assert_eq!(dom::text(&form.elements().error.unwrap()), "Add at least 1");
+    form.reset();
+    assert_eq!(form.parse(), Ok(json!(["a", "b", "c"])));
+    assert!(!form.is_dirty());
+}
+
+#[test]
+fn display() {
+    let elements = Beta::display("Beta", &Beta::C { nix: 4 });
+    assert_eq!(elements[0].get_text(), "C");
+    assert!(elements[0].has_class(rooting_forms::CSS_CLASS_DISPLAY));
+    assert_eq!(elements[1].get_text(), "Something4");
+    let elements = Zeta::display("Zeta", &Zeta {
+        use_auth: false,
+        smtp_password: "hunter2".parse().unwrap(),
+        retries: 2,
+        retry_delay: 10,
+    });
+    let texts = elements.iter().map(|e| e.get_text()).collect::<Vec<_>>();
+    assert_eq!(texts, vec!["Use auth", "", "Retries", "2", "Retry delay", "10"]);
+    let elements = Zeta::display("Zeta", &Zeta {
+        use_auth: true,
+        smtp_password: "hunter2".parse().unwrap(),
+        retries: 0,
+        retry_delay: 10,
+    });
+    let texts = elements.iter().map(|e| e.get_text()).collect::<Vec<_>>();
+    assert_eq!(texts, vec!["Use auth", "", "SMTP password", "••••••••", "Retries", "0"]);
+    let elements = rooting_forms::BigString::display("Bio", &"a\nb".parse().unwrap());
+    assert_eq!(find(&elements, "br").len(), 1);
+    assert_eq!(elements[0].get_text(), "ab");
+}
+
+#[test]
+fn set_disabled() {
+    let form = Delta::new_form("Delta");
+    let elements = form.elements().elements;
+    let mut inputs = find(&elements, "input");
+    inputs.extend(find(&elements, "select"));
+    form.field(&["number", "Some"]).unwrap().input.ref_attr("disabled", "disabled");
+    form.set_disabled(true);
+    assert!(inputs.iter().all(|i| dom::disabled(i)));
+    form.set_disabled(false);
+    let number = form.field(&["number", "Some"]).unwrap().input;
+    assert!(dom::disabled(&number));
+    assert_eq!(inputs.iter().filter(|i| dom::disabled(i)).count(), 1);
+}
+
+#[test]
+fn focus_first_invalid() {
+    let form = Gamma::new_form("Gamma");
+    form.fill("alpha.a", "x");
+    form.fill("flat_alpha.a", "y");
+    assert!(form.parse().is_err());
+    let mut invalid = vec![];
+    form.visit_errors(&mut vec![], &mut |path, _| invalid.push(path.join(".")));
+    assert_eq!(invalid, vec!["alpha.a", "flat_alpha.a"]);
+    assert!(form.focus_first_invalid());
+    assert_eq!(dom::value(&dom::focused().unwrap()), "x");
+    form.fill("alpha.a", "1");
+    assert!(form.parse().is_err());
+    assert!(form.focus_first_invalid());
+    assert_eq!(dom::value(&dom::focused().unwrap()), "y");
+    form.fill("flat_alpha.a", "2");
+    assert!(form.parse().is_ok());
+    assert!(!form.focus_first_invalid());
+}
+
+#[test]
+fn error_summary() {
+    let form = ErrorSummary::new(Gamma::new_form("Gamma"));
+    assert!(form.el.has_class(rooting_forms::CSS_CLASS_HIDDEN));
+    form.fill("alpha.a", "x");
+    form.fill("flat_alpha.a", "y");
+    assert!(form.parse().is_err());
+    assert!(!form.el.has_class(rooting_forms::CSS_CLASS_HIDDEN));
+    let entries = find(&[form.el.clone()], "a");
+    assert_eq!(
+        entries.iter().map(|e| dom::text(e)).collect::<Vec<_>>(),
+        vec!["A (alpha.a): invalid digit found in string", "A (flat_alpha.a): invalid digit found in string"]
+    );
+    for (entry, path) in entries.iter().zip(["alpha", "flat_alpha"]) {
+        let href = entry.get_attr("href").unwrap();
+        assert!(href.ends_with(&format!("-{}.a", path)));
+        assert_eq!(Some(&href[1..]), form.field(&[path, "a"]).unwrap().input.get_attr("id").as_deref());
+    }
+    let other = ErrorSummary::new(Alpha::new_form("Alpha"));
+    other.field(&["a"]).unwrap().input.ref_attr("id", "alpha_a");
+    other.fill("a", "x");
+    assert!(other.parse().is_err());
+    assert_eq!(dom::text(&find(&[other.el.clone()], "a")[0]), "A: invalid digit found in string");
+    // Ids set by the app are kept
+    assert_eq!(find(&[other.el.clone()], "a")[0].get_attr("href").unwrap(), "#alpha_a");
+    assert_eq!(other.field(&["a"]).unwrap().input.get_attr("id").unwrap(), "alpha_a");
+    dom::dispatch(&entries[1], "click");
+    assert_eq!(dom::value(&dom::focused().unwrap()), "y");
+    form.fill("alpha.a", "1");
+    form.fill("flat_alpha.a", "2");
+    assert!(form.parse().is_ok());
+    assert!(form.el.has_class(rooting_forms::CSS_CLASS_HIDDEN));
+    assert!(find(&[form.el.clone()], "a").is_empty());
+}
+
+#[test]
+fn form_with() {
+    let form = Theta::new_form("Theta");
+    let inputs = find(&form.elements().elements, "input");
+    assert_eq!(inputs[0].get_attr("type").unwrap(), "password");
+    let notes = form.field(&["notes"]).unwrap().input;
+    assert_eq!(notes.get_tag(), "textarea");
+    assert!(notes.has_class(rooting_forms::CSS_CLASS_BIG_INPUT));
+    form.fill("password", "hunter2");
+    form.fill("notes", "Hello");
+    form.fill("code", "abc");
+    let theta = form.parse().unwrap();
+    assert_eq!(theta.password, "hunter2");
+    assert_eq!(theta.notes, "Hello");
+    assert_eq!(theta.code, "ABC");
+    let display = find(&Theta::display("Theta", &theta), "span");
+    assert!(display.iter().all(|e| dom::text(e) != "hunter2"));
+    assert!(display.iter().any(|e| dom::text(e) == "ABC"));
+    assert_eq!(Theta::schema("Theta")["properties"]["password"]["format"], "password");
+    // Widgets on tuple variant fields
+    let form = Credential::new_form("Credential");
+    assert_eq!(form.field(&["Password"]).unwrap().input.get_attr("type").unwrap(), "password");
+    form.choose("", "Key");
+    assert_eq!(form.field(&["Key"]).unwrap().input.get_tag(), "textarea");
+    form.fill("Key", "ssh-ed25519");
+    assert_eq!(form.parse(), Ok(Credential::Key("ssh-ed25519".to_string())));
+    assert_eq!(Credential::schema("Credential")["oneOf"][0]["properties"]["Password"]["format"], "password");
+}
+
+#[test]
+fn recursive_enum() {
+    let form = Expr::new_form("Expr");
+    assert_eq!(find(&form.elements().elements, "select").len(), 1);
+    form.fill("Lit", "1");
+    form.choose("", "Add");
+    form.choose("Add.left", "Negate");
+    form.fill("Add.left.Neg.Lit", "2");
+    form.fill("Add.right.Lit", "3");
+    let add = Expr::Add {
+        left: Box::new(Expr::Neg(Box::new(Expr::Lit(2)))),
+        right: Box::new(Expr::Lit(3)),
+    };
+    assert_eq!(form.parse().unwrap(), add);
+    form.choose("", "Literal");
+    assert_eq!(form.parse().unwrap(), Expr::Lit(1));
+    form.choose("", "Add");
+    assert_eq!(form.parse().unwrap(), add);
+    let from = Expr::Neg(Box::new(Expr::Lit(4)));
+    let form = Expr::new_form_with("Expr", Some(&from));
+    assert_eq!(form.parse().unwrap(), from);
+    // Recursive uses of the type in the schema refer back to the outermost one
+    let schema = Expr::schema("Expr");
+    assert_eq!(schema["$anchor"], "Expr");
+    assert_eq!(schema["oneOf"][1]["properties"]["Neg"], rooting_forms::serde_json::json!({
+        "title": "Negate",
+        "$ref": "#Expr"
+    }));
+    assert_eq!(schema["oneOf"][2]["properties"]["Add"]["properties"]["left"]["$ref"], "#Expr");
+    assert_eq!(Expr::schema("Expr"), schema);
+    let schema = <Option<Expr> as Form>::schema("Expr");
+    assert_eq!(schema.to_string().matches("\"$anchor\":\"Expr\"").count(), 1);
+    assert!(rooting_forms::dynamic::DynamicForm::new("Expr", &schema, None).is_err());
+}
+
+#[test]
+fn wrappers() {
+    let iota = Iota {
+        shared: std::rc::Rc::new(Alpha { a: 1 }),
+        count: std::sync::Arc::new(2),
+        name: std::borrow::Cow::Borrowed("three"),
+        counter: std::num::Wrapping(4),
+    };
+    let form = Iota::new_form_with("Iota", Some(&iota));
+    assert_eq!(find(&form.elements().elements, "fieldset").len(), 1);
+    assert_eq!(form.parse().unwrap(), iota);
+    form.fill("shared.a", "5");
+    form.fill("name", "six");
+    form.fill("counter", "256");
+    assert!(form.parse().is_err());
+    assert_eq!(form.error_text("counter"), "number too large to fit in target type");
+    form.fill("counter", "7");
+    let edited = form.parse().unwrap();
+    assert_eq!(edited.shared.a, 5);
+    assert_eq!(edited.name, "six");
+    assert_eq!(edited.counter.0, 7);
+    let display = find(&Iota::display("Iota", &iota), "span");
+    assert!(display.iter().any(|e| dom::text(e) == "three"));
+    assert_eq!(Iota::schema("Iota")["properties"]["counter"]["maximum"], 255);
+}
+
+#[test]
+fn tuples_arrays() {
+    let from = Kappa {
+        point: (1.5, -2.),
+        ip: [10, 0, 0, 1],
+    };
+    let form = Kappa::new_form_with("Kappa", Some(&from));
+    let elements = form.elements().elements;
+    let rows = elements.iter().filter(|e| e.has_class(rooting_forms::CSS_CLASS_ROW)).collect::<Vec<_>>();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(
+        find(&[rows[1].clone()], "input").iter().map(|i| i.get_attr("aria-label").unwrap()).collect::<Vec<_>>(),
+        vec!["IP 1", "IP 2", "IP 3", "IP 4"]
+    );
+    assert_eq!(form.parse().unwrap(), from);
+    form.fill("point.1", "x");
+    form.fill("ip.2", "300");
+    assert!(form.parse().is_err());
+    assert_eq!(form.error_text("point.0"), "");
+    assert_eq!(form.error_text("point.1"), "invalid float literal");
+    assert_eq!(form.error_text("ip.2"), "number too large to fit in target type");
+    form.fill("point.1", "3");
+    form.fill("ip.2", "2");
+    assert_eq!(form.parse().unwrap(), Kappa {
+        point: (1.5, 3.),
+        ip: [10, 0, 2, 1],
+    });
+    let display = find(&Kappa::display("Kappa", &from), "span");
+    assert_eq!(display.iter().filter(|e| e.get_attr("aria-label").as_deref() == Some("IP 4")).count(), 1);
+    let schema = Kappa::schema("Kappa");
+    assert_eq!(schema["properties"]["ip"]["maxItems"], 4);
+    assert_eq!(schema["properties"]["point"]["prefixItems"][1]["title"], "Point 2");
+}
+
+#[test]
+fn checkbox_group() {
+    let from = Lambda {
+        permissions: [Permission::Write].into_iter().collect(),
+        notify: vec![Permission::Read],
+    };
+    let form = Lambda::new_form_with("Lambda", Some(&from));
+    assert_eq!(find(&form.elements().elements, "input").len(), 6);
+    assert_eq!(form.parse().unwrap(), from);
+    form.toggle("permissions.Read", true);
+    form.toggle("permissions.Write", false);
+    form.toggle("notify.Read", false);
+    assert!(form.parse().is_err());
+    assert_eq!(form.error_text("notify.Admin"), "Select at least 1");
+    let mut invalid = vec![];
+    form.visit_errors(&mut vec![], &mut |path, _| invalid.push(path.join(".")));
+    assert_eq!(invalid, vec!["notify.Read"]);
+    form.toggle("notify.Read", true);
+    form.toggle("notify.Write", true);
+    form.toggle("notify.Admin", true);
+    assert!(form.parse().is_err());
+    assert_eq!(form.error_text("notify.Read"), "Select at most 2");
+    form.toggle("notify.Write", false);
+    let lambda = form.parse().unwrap();
+    assert_eq!(lambda, Lambda {
+        permissions: [Permission::Read].into_iter().collect(),
+        notify: vec![Permission::Read, Permission::Admin],
+    });
+    assert_eq!(form.error_text("notify.Read"), "");
+    let display = find(&Lambda::display("Lambda", &lambda), "input");
+    assert_eq!(display.iter().filter(|e| dom::checked(e)).count(), 3);
+    let schema = Lambda::schema("Lambda");
+    assert_eq!(schema["properties"]["permissions"]["items"]["oneOf"][2]["const"], "Admin");
+    assert_eq!(schema["properties"]["permissions"].get("maxItems"), None);
+    assert_eq!(schema["properties"]["notify"]["minItems"], 1);
+    assert_eq!(schema["properties"]["notify"]["maxItems"], 2);
+}
+
+#[test]
+fn select() {
+    let form = <WeekdaySelect as rooting_forms::FormWith<Weekday>>::new_form_with("Day", Some(&Weekday::Tue));
+    let select = &find(&form.elements().elements, "select")[0];
+    assert_eq!(
+        dom::options(select),
+        vec![("Mon".to_string(), "Mon".to_string()), ("Tue".to_string(), "Tue".to_string()), ("Wed".to_string(), "Wed".to_string())]
+    );
+    assert_eq!(form.parse(), Ok(Weekday::Tue));
+    assert!(!form.is_dirty());
+    form.choose("", "Wed");
+    assert!(form.is_dirty());
+    assert_eq!(form.parse(), Ok(Weekday::Wed));
+    form.reset();
+    assert_eq!(form.parse(), Ok(Weekday::Tue));
+    form.fill("", "Sun");
+    assert!(form.parse().is_err());
+    assert_eq!(form.error_text(""), "Matching variant not found");
+    let schema = <WeekdaySelect as rooting_forms::FormWith<Weekday>>::schema("Day");
+    assert_eq!(schema["enum"], rooting_forms::serde_json::json!(["Mon", "Tue", "Wed"]));
+    // An initial value that isn't an option falls back to the first option
+    let form = rooting_forms::SelectFormState::new("Day", [Weekday::Mon, Weekday::Tue], Some(&Weekday::Wed));
+    assert_eq!(form.parse(), Ok(Weekday::Mon));
+    assert!(!form.is_dirty());
+    form.choose("", "Tue");
+    form.reset();
+    assert_eq!(form.parse(), Ok(Weekday::Mon));
+}
+
+#[cfg(feature = "strum")]
+#[test]
+fn select_strum() {
+    let form = Mu::new_form("Mu");
+    assert_eq!(form.parse(), Ok(Mu {
+        day: Weekday::Mon,
+        backup_day: Weekday::Mon,
+    }));
+    form.choose("backup_day", "Wed");
+    assert_eq!(form.parse().unwrap().backup_day, Weekday::Wed);
+    assert_eq!(Mu::schema("Mu")["properties"]["backup_day"]["enum"][2], "Wed");
+}
+
+#[test]
+fn serde_attrs() {
+    use rooting_forms::{
+        dynamic::DynamicForm,
+        serde_json::json,
+    };
+
+    let form = Nu::new_form("Nu");
+    let elements = form.elements().elements;
+    // Flattened, so no fieldset for `listen`
+    assert!(find(&elements, "legend").is_empty());
+    let labels = find(&elements, "span").iter().filter(|e| e.has_class(rooting_forms::CSS_CLASS_LABEL)).map(|e| dom::text(e)).collect::<Vec<_>>();
+    assert_eq!(labels, vec!["Host", "Port", "Workers", "Mode"]);
+    form.fill("listen.host", "localhost");
+    assert_eq!(form.parse().unwrap(), Nu {
+        listen: Listen {
+            host: "localhost".to_string(),
+            port: 8080,
+        },
+        workers: 0,
+        cache: vec![],
+        mode: Mode::Fast,
+    });
+    assert_eq!(dom::options(&form.field(&["mode"]).unwrap().input)[1].1, "Careful mode");
+    form.choose("mode", "Careful mode");
+    form.fill("mode.Careful.retries", "3");
+    assert_eq!(form.parse().unwrap().mode, Mode::Careful {
+        retries: 3,
+        fallback_port: 8080,
+    });
+    // The schema describes the serialized form, so data matching it deserializes
+    let schema = Nu::schema("Nu");
+    assert_eq!(
+        schema["properties"].as_object().unwrap().keys().collect::<Vec<_>>(),
+        vec!["Host", "port", "Workers", "mode"]
+    );
+    assert_eq!(schema["required"], json!(["Host", "mode"]));
+    assert_eq!(schema["properties"]["mode"]["oneOf"][0]["const"], "Fast mode");
+    assert_eq!(schema["properties"]["mode"]["oneOf"][1]["required"], json!(["Careful mode"]));
+    let form = DynamicForm::new("Nu", &schema, None).unwrap();
+    form.fill("Host", "localhost");
+    form.choose("mode", "Careful mode");
+    form.fill("mode.Careful mode.Careful mode.Retries", "3");
+    let value = form.parse().unwrap();
+    assert_eq!(rooting_forms::serde_json::from_value::<Nu>(value).unwrap(), Nu {
+        listen: Listen {
+            host: "localhost".to_string(),
+            port: 8080,
+        },
+        workers: 0,
+        cache: vec![],
+        mode: Mode::Careful {
+            retries: 3,
+            fallback_port: 8080,
+        },
+    });
+    // Fields default to the parts of the type's default
+    let form = Pi::new_form("Pi");
+    assert_eq!(form.parse().unwrap(), Pi::default());
+    form.toggle("custom_port", true);
+    form.fill("port", "1");
+    assert_eq!(form.parse().unwrap().port, 1);
+    assert_eq!(Pi::schema("Pi")["required"], json!([]));
+}
+
+/// A future that stays pending until `open`ed.
+#[derive(Clone, Default)]
+struct Gate(std::rc::Rc<std::cell::RefCell<(bool, Option<std::task::Waker>)>>);
+
+impl Gate {
+    fn open(&self) {
+        let waker = {
+            let mut s = self.0.borrow_mut();
+            s.0 = true;
+            s.1.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl std::future::Future for Gate {
+    type Output = ();
+
+    fn poll(self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> std::task::Poll<()> {
+        let mut s = self.0.borrow_mut();
+        if s.0 {
+            return std::task::Poll::Ready(());
+        }
+        s.1 = Some(cx.waker().clone());
+        return std::task::Poll::Pending;
+    }
+}
+
+/// Fire `submit` at the form, returning whether the default was prevented.
+fn submit<T>(form: &SubmitForm<T>) -> bool {
+    let ev = dom::Event::new("submit");
+    form.el.dispatch(&ev);
+    return ev.default_prevented();
+}
+
+#[test]
+fn submit_form() {
+    let submitted = std::rc::Rc::new(std::cell::RefCell::new(vec![]));
+    let form = SubmitForm::new(Alpha::new_form("Alpha"), "Save", {
+        let submitted = submitted.clone();
+        move |v| submitted.borrow_mut().push(v)
+    });
+    assert_eq!(find(&[form.el.clone()], "button")[0].get_attr("type").unwrap(), "submit");
+    form.state.fill("a", "4");
+    assert!(submit(&form));
+    assert_eq!(*submitted.borrow(), vec![Alpha { a: 4 }]);
+}
+
+#[test]
+fn submit_form_invalid() {
+    let submitted = std::rc::Rc::new(std::cell::Cell::new(0));
+    let form = SubmitForm::new(Gamma::new_form("Gamma"), "Save", {
+        let submitted = submitted.clone();
+        move |_| submitted.set(submitted.get() + 1)
+    });
+    form.state.fill("alpha.a", "1");
+    form.state.fill("flat_alpha.a", "y");
+    assert!(submit(&form));
+    assert_eq!(submitted.get(), 0);
+    assert_eq!(dom::value(&dom::focused().unwrap()), "y");
+}
+
+#[test]
+fn submit_form_async() {
+    let submitted = std::rc::Rc::new(std::cell::RefCell::new(vec![]));
+    let gate = Gate::default();
+    let form = SubmitForm::new_async(Alpha::new_form("Alpha"), "Save", {
+        let submitted = submitted.clone();
+        let gate = gate.clone();
+        move |v| {
+            submitted.borrow_mut().push(v);
+            gate.clone()
+        }
+    });
+    let input = form.state.field(&["a"]).unwrap().input;
+    let button = find(&[form.el.clone()], "button")[0].clone();
+    form.state.fill("a", "4");
+    assert!(submit(&form));
+    assert_eq!(submitted.borrow().len(), 1);
+    assert!(dom::disabled(&input));
+    assert!(dom::disabled(&button));
+    form.state.fill("a", "5");
+    assert!(submit(&form));
+    assert_eq!(*submitted.borrow(), vec![Alpha { a: 4 }]);
+    gate.open();
+    assert!(!dom::disabled(&input));
+    assert!(!dom::disabled(&button));
+    submit(&form);
+    assert_eq!(*submitted.borrow(), vec![Alpha { a: 4 }, Alpha { a: 5 }]);
+}
+
+#[test]
+fn steps_form() {
+    let (form, steps) = Eta::new_steps_form(None);
+    assert_eq!(steps.iter().map(|s| s.title).collect::<Vec<_>>(), vec!["Account", "Profile"]);
+    assert_eq!(find(&steps[0].elements, "input").len(), 3);
+    form.fill("age", "x");
+    form.fill("lucky_number", "7");
+    assert!(!(steps[0].validate)());
+    assert!((steps[1].validate)());
+    form.fill("age", "30");
+    assert!((steps[0].validate)());
+}
+
+#[test]
+fn wizard() {
+    let finished = std::rc::Rc::new(std::cell::RefCell::new(vec![]));
+    let wizard = Wizard::new(None, WizardText {
+        back: "Back",
+        next: "Next",
+        finish: "Finish",
+    }, {
+        let finished = finished.clone();
+        move |v: Eta| finished.borrow_mut().push((v.username, v.age, v.display_name, v.lucky_number, v.show_display_name))
+    });
+    let form = wizard.state.clone();
+    let step_els = find(&[wizard.el.clone()], "fieldset");
+    let current = || step_els.iter().position(|e| !e.has_class(rooting_forms::CSS_CLASS_HIDDEN)).unwrap();
+    let buttons = find(&[wizard.el.clone()], "button");
+    let (back, next) = (&buttons[0], &buttons[1]);
+    let submit = || wizard.el.dispatch(&dom::Event::new("submit"));
+    assert_eq!(current(), 0);
+    assert!(back.has_class(rooting_forms::CSS_CLASS_HIDDEN));
+    assert_eq!(next.get_text(), "Next");
+
+    // An invalid step 1 field stops at step 1, without validating step 2
+    form.fill("username", "ann");
+    form.fill("age", "x");
+    dom::set_value(&form.field(&["lucky_number"]).unwrap().input, "y");
+    submit();
+    assert_eq!(current(), 0);
+    assert_eq!(form.error_text("age"), "invalid digit found in string");
+    assert_eq!(form.error_text("lucky_number"), "");
+    assert_eq!(form.field(&["lucky_number"]).unwrap().get_value(), "y");
+    assert_eq!(dom::value(&dom::focused().unwrap()), "x");
+    form.fill("age", "30");
+    submit();
+    assert_eq!(current(), 1);
+    assert_eq!(next.get_text(), "Finish");
+
+    // Back keeps entered values
+    form.fill("display_name", "Ann");
+    dom::dispatch(back, "click");
+    assert_eq!(current(), 0);
+    assert_eq!(form.field(&["age"]).unwrap().get_value(), "30");
+    submit();
+    assert_eq!(current(), 1);
+    assert_eq!(form.field(&["display_name"]).unwrap().get_value(), "Ann");
+
+    // Finishing parses everything
+    submit();
+    assert!(finished.borrow().is_empty());
+    assert_eq!(form.error_text("lucky_number"), "invalid digit found in string");
+    form.fill("lucky_number", "7");
+    form.toggle("show_display_name", true);
+    submit();
+    assert_eq!(*finished.borrow(), vec![("ann".to_string(), 30, "Ann".to_string(), 7, true)]);
+}
+
+#[test]
+fn draft() {
+    let form: std::rc::Rc<dyn FormState<Delta>> = std::rc::Rc::from(Delta::new_form("Delta"));
+    let draft = Draft::new("delta", &form);
+    form.toggle("number", true);
+    form.fill("number.Some", "x\"\\\n");
+    form.toggle("beta", true);
+    form.choose("beta.Some", "C");
+    form.fill("beta.Some.C.nix", "4");
+    assert!(dom::storage_get("delta").is_some());
+
+    // A new form restores everything, including the invalid text
+    let restored: std::rc::Rc<dyn FormState<Delta>> = std::rc::Rc::from(Delta::new_form("Delta"));
+    let _restored_draft = Draft::new("delta", &restored);
+    assert_eq!(restored.get_raw(), form.get_raw());
+    assert_eq!(restored.field(&["number"]).unwrap().get_value(), "true");
+    assert_eq!(restored.field(&["number", "Some"]).unwrap().get_value(), "x\"\\\n");
+    assert_eq!(restored.field(&["beta", "Some"]).unwrap().get_value(), "2");
+    assert!(restored.parse().is_err());
+    restored.fill("number.Some", "5");
+    let delta = restored.parse().unwrap();
+    assert_eq!(delta.number, Some(5));
+    assert_eq!(delta.beta, Some(Beta::C { nix: 4 }));
+
+    // Cleared drafts aren't restored
+    draft.clear();
+    assert!(dom::storage_get("delta").is_none());
+    let fresh = Delta::new_form("Delta");
+    let fresh: std::rc::Rc<dyn FormState<Delta>> = std::rc::Rc::from(fresh);
+    let _fresh_draft = Draft::new("delta", &fresh);
+    assert_eq!(fresh.field(&["number"]).unwrap().get_value(), "false");
+    assert!(!fresh.is_dirty());
+}
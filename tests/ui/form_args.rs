@@ -0,0 +1,70 @@
+#[derive(rooting_forms::Form)]
+struct Unknown {
+    #[title("A")]
+    #[form(flaten)]
+    a: i32,
+    #[title("B")]
+    #[form(colour = "red")]
+    b: i32,
+}
+
+#[derive(rooting_forms::Form)]
+struct BadValues {
+    #[title("A")]
+    #[form(step = 1)]
+    a: i32,
+    #[title("B")]
+    #[form(with)]
+    b: String,
+}
+
+#[derive(rooting_forms::Form)]
+struct BadMulti {
+    #[title("A")]
+    #[form(multi(least = 1))]
+    a: Vec<i32>,
+    #[title("B")]
+    #[form(multi(max = "2"))]
+    b: Vec<i32>,
+    #[title("C")]
+    #[form(multi, with = rooting_forms::Password)]
+    c: Vec<i32>,
+}
+
+#[derive(rooting_forms::Form)]
+#[form(serdes)]
+struct BadType {
+    #[title("A")]
+    a: i32,
+}
+
+#[derive(rooting_forms::Form, serde::Deserialize)]
+#[form(serde)]
+struct BadDefault {
+    #[title("A")]
+    #[serde(default = "not a path")]
+    a: i32,
+}
+
+#[derive(rooting_forms::Form)]
+enum VariantArgs {
+    #[title("A")]
+    #[form(bogus)]
+    A(#[form(wiht = rooting_forms::Password, nonsense)] String),
+    #[title("B")]
+    #[form(flatten)]
+    B(#[form(visible_if = "a")] String),
+}
+
+#[derive(rooting_forms::Form)]
+#[form(sered)]
+struct TypeTypo {
+    #[title("A")]
+    #[form(visible_if(a = "not a path"))]
+    a: bool,
+    #[title("B")]
+    #[form(visible_if = "not a field")]
+    b: bool,
+}
+
+fn main() { }
@@ -0,0 +1,20 @@
+#[derive(rooting_forms::Form)]
+struct FirstWithoutStep {
+    #[title("A")]
+    a: i32,
+    #[title("B")]
+    #[form(step = "Second")]
+    b: i32,
+}
+
+#[derive(rooting_forms::Form)]
+enum InVariant {
+    #[title("A")]
+    A {
+        #[title("A")]
+        #[form(step = "First")]
+        a: i32,
+    },
+}
+
+fn main() { }
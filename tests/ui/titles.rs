@@ -0,0 +1,31 @@
+#[derive(rooting_forms::Form)]
+struct Missing {
+    a: i32,
+    #[title("B")]
+    b: i32,
+    c: i32,
+}
+
+#[derive(rooting_forms::Form)]
+struct Duplicate {
+    #[title("A")]
+    #[title("Also A")]
+    a: i32,
+}
+
+#[derive(rooting_forms::Form)]
+struct NotString {
+    #[title(1)]
+    a: i32,
+    #[title("B", "C")]
+    b: i32,
+}
+
+#[derive(rooting_forms::Form)]
+enum MissingVariant {
+    A,
+    #[title("B")]
+    B,
+}
+
+fn main() { }
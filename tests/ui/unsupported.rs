@@ -0,0 +1,21 @@
+#[derive(rooting_forms::Form)]
+struct Tuple(i32, i32);
+
+#[derive(rooting_forms::Form)]
+struct Unit;
+
+#[derive(rooting_forms::Form)]
+union Union {
+    a: i32,
+}
+
+#[derive(rooting_forms::Form)]
+enum MultiFieldVariant {
+    #[title("A")]
+    A(i32, i32),
+}
+
+#[derive(rooting_forms::Form)]
+enum Empty { }
+
+fn main() { }
@@ -0,0 +1,26 @@
+#[derive(rooting_forms::Form)]
+struct BadValues {
+    #[title("A")]
+    a: bool,
+    #[title("B")]
+    #[form(visible_if = a)]
+    b: i32,
+    #[title("C")]
+    #[form(visible_if("a" = "is_positive"))]
+    c: i32,
+    #[title("D")]
+    #[form(visible_if(a = is_positive))]
+    d: i32,
+    #[title("E")]
+    #[form(visible_if(a = "is_positive", b = "is_positive"))]
+    e: i32,
+}
+
+#[derive(rooting_forms::Form)]
+struct MissingSibling {
+    #[title("A")]
+    #[form(visible_if = "enabled")]
+    a: i32,
+}
+
+fn main() { }